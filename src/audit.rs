@@ -0,0 +1,203 @@
+use crate::filter::glob_match;
+use crate::port::Binding;
+use crate::process::ProcessInfo;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// the live state matches the policy
+pub const EXIT_OK: i32 = 0;
+/// at least one violation was found
+pub const EXIT_VIOLATIONS: i32 = 1;
+
+/// expected listeners read from `portsage.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    #[serde(rename = "listener")]
+    pub listeners: Vec<Expected>,
+}
+
+/// one allowed listener
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    pub port: u16,
+    /// glob matched against the process name
+    #[serde(default)]
+    pub process: Option<String>,
+    #[serde(default)]
+    pub bind: Bind,
+    /// do not report the listener when it is not running
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// where an expected listener may be bound
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Bind {
+    #[default]
+    Any,
+    Loopback,
+    Addr(String),
+}
+
+impl From<String> for Bind {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "any" => Bind::Any,
+            "loopback" => Bind::Loopback,
+            "0.0.0.0" | "::" | "[::]" => Bind::Addr("*".into()),
+            _ => Bind::Addr(s.trim_start_matches('[').trim_end_matches(']').into()),
+        }
+    }
+}
+
+impl From<Bind> for String {
+    fn from(b: Bind) -> Self {
+        b.to_string()
+    }
+}
+
+impl fmt::Display for Bind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bind::Any => write!(f, "any"),
+            Bind::Loopback => write!(f, "loopback"),
+            Bind::Addr(addr) => write!(f, "{addr}"),
+        }
+    }
+}
+
+impl Bind {
+    pub fn allows(&self, binding: &Binding) -> bool {
+        match self {
+            Bind::Any => true,
+            Bind::Loopback => binding.is_loopback(),
+            Bind::Addr(addr) if addr == "*" => binding.is_exposed(),
+            Bind::Addr(addr) => *addr == binding.addr,
+        }
+    }
+}
+
+impl Expected {
+    fn matches(&self, port: u16, name: &str) -> bool {
+        self.port == port && self.process.iter().all(|p| glob_match(p, name))
+    }
+}
+
+impl Policy {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid policy file {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationKind {
+    /// a listener that no policy entry allows
+    Unexpected,
+    /// an expected listener that is not running
+    Missing,
+    /// an expected listener bound to the wrong address
+    Bind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    pub port: u16,
+    pub pid: Option<i32>,
+    /// the running process, or the expected pattern for missing listeners
+    pub name: Option<String>,
+    pub addr: Option<String>,
+    pub expected_bind: Option<Bind>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.name.as_deref().unwrap_or("any process");
+        match self.kind {
+            ViolationKind::Missing => write!(f, "missing     {:<5}  {name}", self.port),
+            _ => {
+                let label = match self.kind {
+                    ViolationKind::Unexpected => "unexpected",
+                    _ => "bind",
+                };
+                write!(f, "{label:<10}  {:<5}  {name}", self.port)?;
+                if let Some(pid) = self.pid {
+                    write!(f, "[{pid}]")?;
+                }
+                if let Some(ref addr) = self.addr {
+                    write!(f, " on {addr}")?;
+                }
+                match self.expected_bind {
+                    Some(ref bind) => write!(f, ", expected {bind}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// compare the listeners of `processes` against the policy
+pub fn audit_processes(policy: &Policy, processes: &[ProcessInfo]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut seen = vec![false; policy.listeners.len()];
+
+    for p in processes {
+        for binding in &p.bindings {
+            let matching: Vec<usize> = (0..policy.listeners.len())
+                .filter(|&i| policy.listeners[i].matches(binding.port, &p.name))
+                .collect();
+            let violation = |kind, expected_bind| Violation {
+                kind,
+                port: binding.port,
+                pid: Some(p.pid),
+                name: Some(p.name.clone()),
+                addr: Some(binding.to_string()),
+                expected_bind,
+            };
+
+            if matching.is_empty() {
+                violations.push(violation(ViolationKind::Unexpected, None));
+                continue;
+            }
+            for &i in &matching {
+                seen[i] = true;
+            }
+            if !matching
+                .iter()
+                .any(|&i| policy.listeners[i].bind.allows(binding))
+            {
+                let bind = policy.listeners[matching[0]].bind.clone();
+                violations.push(violation(ViolationKind::Bind, Some(bind)));
+            }
+        }
+    }
+
+    for (expected, seen) in policy.listeners.iter().zip(seen) {
+        if !seen && !expected.optional {
+            violations.push(Violation {
+                kind: ViolationKind::Missing,
+                port: expected.port,
+                pid: None,
+                name: expected.process.clone(),
+                addr: None,
+                expected_bind: None,
+            });
+        }
+    }
+
+    violations.sort_by_key(|v| (v.port, v.pid));
+    violations
+}
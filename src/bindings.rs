@@ -1,14 +1,57 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer};
 
-#[derive(Debug, Clone)]
+/// keys for each TUI action; read from `[keys]` in the config file, where
+/// actions that are not set keep their defaults
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct KeyBindings {
+    #[serde(deserialize_with = "deserialize_keys")]
     pub up: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub down: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub page_up: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub page_down: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub half_page_up: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub half_page_down: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub top: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub bottom: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub quit: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub detail: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub filter: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub copy: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub mark: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub kill: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub listening: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub columns: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub layout: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub refresh: Vec<KeyEvent>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub help: Vec<KeyEvent>,
+}
+
+/// the keys of one TUI mode, for the help overlay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub mode: &'static str,
+    /// formatted keys and what they do
+    pub entries: Vec<(String, String)>,
 }
 
 impl Default for KeyBindings {
@@ -25,6 +68,21 @@ impl Default for KeyBindings {
                 KeyEvent::new(Char('j'), KeyModifiers::NONE),
                 KeyEvent::new(Char('n'), KeyModifiers::CONTROL),
             ],
+            page_up: vec![
+                KeyEvent::new(PageUp, KeyModifiers::NONE),
+                KeyEvent::new(Char('b'), KeyModifiers::CONTROL),
+            ],
+            page_down: vec![
+                KeyEvent::new(PageDown, KeyModifiers::NONE),
+                KeyEvent::new(Char('f'), KeyModifiers::CONTROL),
+            ],
+            half_page_up: vec![KeyEvent::new(Char('u'), KeyModifiers::CONTROL)],
+            half_page_down: vec![KeyEvent::new(Char('d'), KeyModifiers::CONTROL)],
+            top: vec![KeyEvent::new(Home, KeyModifiers::NONE)],
+            bottom: vec![
+                KeyEvent::new(End, KeyModifiers::NONE),
+                KeyEvent::new(Char('G'), KeyModifiers::SHIFT),
+            ],
             quit: vec![
                 KeyEvent::new(Char('q'), KeyModifiers::NONE),
                 KeyEvent::new(Esc, KeyModifiers::NONE),
@@ -32,12 +90,134 @@ impl Default for KeyBindings {
             detail: vec![KeyEvent::new(Tab, KeyModifiers::NONE)],
             filter: vec![KeyEvent::new(Char(':'), KeyModifiers::NONE)],
             copy: vec![KeyEvent::new(Enter, KeyModifiers::NONE)],
+            mark: vec![KeyEvent::new(Char(' '), KeyModifiers::NONE)],
             kill: vec![KeyEvent::new(Char('x'), KeyModifiers::NONE)],
+            listening: vec![KeyEvent::new(Char('l'), KeyModifiers::NONE)],
+            columns: vec![KeyEvent::new(Char('c'), KeyModifiers::NONE)],
+            layout: vec![KeyEvent::new(Char('v'), KeyModifiers::NONE)],
+            refresh: vec![KeyEvent::new(Char('r'), KeyModifiers::NONE)],
+            help: vec![KeyEvent::new(Char('?'), KeyModifiers::NONE)],
         }
     }
 }
 
 impl KeyBindings {
+    /// every action with its config key and description, in declaration order
+    pub fn actions(&self) -> [(&'static str, &'static str, &[KeyEvent]); 19] {
+        [
+            ("up", "Move up", &self.up),
+            ("down", "Move down", &self.down),
+            ("page_up", "Page up", &self.page_up),
+            ("page_down", "Page down", &self.page_down),
+            ("half_page_up", "Half a page up", &self.half_page_up),
+            ("half_page_down", "Half a page down", &self.half_page_down),
+            ("top", "Go to the first row (also g g)", &self.top),
+            ("bottom", "Go to the last row", &self.bottom),
+            ("quit", "Quit", &self.quit),
+            ("detail", "Show detail", &self.detail),
+            ("filter", "Enter filter mode", &self.filter),
+            ("copy", "Copy PID, ports, command line, ...", &self.copy),
+            ("mark", "Mark the process for copying", &self.mark),
+            ("kill", "Kill process (confirm)", &self.kill),
+            ("listening", "Toggle listening only", &self.listening),
+            ("columns", "Choose columns", &self.columns),
+            ("layout", "Cycle the detail pane layout", &self.layout),
+            ("refresh", "Reload processes", &self.refresh),
+            ("help", "Show this help", &self.help),
+        ]
+    }
+
+    /// the config name of the first action bound to `key`
+    pub fn action_for(&self, key: &KeyEvent) -> Option<&'static str> {
+        self.actions()
+            .into_iter()
+            .find(|(_, _, keys)| keys.contains(key))
+            .map(|(name, _, _)| name)
+    }
+
+    /// the keys in effect, grouped by mode
+    pub fn help_sections(&self) -> Vec<HelpSection> {
+        let keys = |groups: &[&[KeyEvent]]| {
+            groups
+                .concat()
+                .iter()
+                .map(format_key)
+                .collect::<Vec<_>>()
+                .join(" / ")
+        };
+        let section = |mode, entries: &[(&str, &str)]| HelpSection {
+            mode,
+            entries: entries
+                .iter()
+                .map(|(k, action)| (k.to_string(), action.to_string()))
+                .collect(),
+        };
+        let move_keys = keys(&[&self.up, &self.down]);
+
+        vec![
+            HelpSection {
+                mode: "Normal",
+                entries: self
+                    .actions()
+                    .into_iter()
+                    .map(|(_, action, k)| (keys(&[k]), action.to_string()))
+                    .collect(),
+            },
+            section(
+                "Filter",
+                &[
+                    ("enter", "Apply and save to history"),
+                    ("esc", "Leave filter mode"),
+                    ("up / down", "Previous / next filter from history"),
+                    ("backspace", "Delete a character"),
+                ],
+            ),
+            section(
+                "Detail",
+                &[
+                    (&keys(&[&self.quit, &self.detail]), "Close"),
+                    ("left / right / h / l", "Previous / next tab"),
+                    ("1-6", "Go to a tab"),
+                    (&move_keys, "Scroll"),
+                    (&keys(&[&self.copy]), "Copy the tab"),
+                ],
+            ),
+            section(
+                "Copy",
+                &[
+                    ("1-8", "Copy the entry"),
+                    (&move_keys, "Move"),
+                    ("enter", "Copy the selected entry"),
+                    (&keys(&[&self.quit]), "Cancel"),
+                ],
+            ),
+            section(
+                "Confirm kill",
+                &[("y", "Kill the process"), ("n / esc", "Cancel")],
+            ),
+            section(
+                "Columns",
+                &[
+                    (&move_keys, "Move"),
+                    ("space", "Show or hide the column"),
+                    ("enter", "Save to the config file"),
+                    ("esc / q", "Cancel"),
+                ],
+            ),
+            section(
+                "Help",
+                &[
+                    (&move_keys, "Scroll"),
+                    (&keys(&[&self.quit, &self.help]), "Close"),
+                ],
+            ),
+            section(
+                "Always",
+                &[("ctrl-c", "Quit"), ("ctrl-z", "Suspend; resume with fg")],
+            ),
+        ]
+    }
+
     fn matches(key: &KeyEvent, bindings: &[KeyEvent]) -> bool {
         bindings.contains(key)
     }
@@ -50,6 +230,30 @@ impl KeyBindings {
         Self::matches(key, &self.down)
     }
 
+    pub fn is_page_up(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.page_up)
+    }
+
+    pub fn is_page_down(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.page_down)
+    }
+
+    pub fn is_half_page_up(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.half_page_up)
+    }
+
+    pub fn is_half_page_down(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.half_page_down)
+    }
+
+    pub fn is_top(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.top)
+    }
+
+    pub fn is_bottom(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.bottom)
+    }
+
     pub fn is_quit(&self, key: &KeyEvent) -> bool {
         Self::matches(key, &self.quit)
     }
@@ -66,7 +270,123 @@ impl KeyBindings {
         Self::matches(key, &self.copy)
     }
 
+    pub fn is_mark(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.mark)
+    }
+
     pub fn is_kill(&self, key: &KeyEvent) -> bool {
         Self::matches(key, &self.kill)
     }
+
+    pub fn is_listening(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.listening)
+    }
+
+    pub fn is_columns(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.columns)
+    }
+
+    pub fn is_layout(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.layout)
+    }
+
+    pub fn is_refresh(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.refresh)
+    }
+
+    pub fn is_help(&self, key: &KeyEvent) -> bool {
+        Self::matches(key, &self.help)
+    }
+}
+
+/// parse a key such as `q`, `ctrl-k`, `shift-tab` or `pagedown`
+pub fn parse_key(text: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text;
+    // a lone `-` is a key, not a separator
+    while let Some((prefix, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+        modifiers |= match prefix.to_ascii_lowercase().as_str() {
+            "ctrl" | "c" => KeyModifiers::CONTROL,
+            "alt" | "meta" | "m" => KeyModifiers::ALT,
+            "shift" | "s" => KeyModifiers::SHIFT,
+            _ => return Err(format!("unknown modifier `{prefix}` in key `{text}`")),
+        };
+        rest = key;
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_ascii_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            f if f.starts_with('f') && f[1..].parse::<u8>().is_ok() => {
+                KeyCode::F(f[1..].parse().unwrap())
+            }
+            _ => return Err(format!("unknown key `{text}`")),
+        },
+    };
+
+    // terminals report shift-tab as BackTab
+    if code == KeyCode::Tab && modifiers.contains(KeyModifiers::SHIFT) {
+        return Ok(KeyEvent::new(
+            KeyCode::BackTab,
+            modifiers - KeyModifiers::SHIFT,
+        ));
+    }
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+/// the inverse of `parse_key`, used for help text and the default config
+pub fn format_key(key: &KeyEvent) -> String {
+    let mut text = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        text.push_str("ctrl-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        text.push_str("alt-");
+    }
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_)) {
+        text.push_str("shift-");
+    }
+    match key.code {
+        KeyCode::Char(' ') => text.push_str("space"),
+        KeyCode::Char(c) => text.push(c),
+        KeyCode::F(n) => text.push_str(&format!("f{n}")),
+        KeyCode::BackTab => text.push_str("shift-tab"),
+        code => text.push_str(&format!("{code:?}").to_ascii_lowercase()),
+    }
+    text
+}
+
+/// accept either a single key or a list of keys
+fn deserialize_keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<KeyEvent>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let keys = match Keys::deserialize(deserializer)? {
+        Keys::One(key) => vec![key],
+        Keys::Many(keys) => keys,
+    };
+    keys.iter()
+        .map(|k| parse_key(k).map_err(serde::de::Error::custom))
+        .collect()
 }
@@ -0,0 +1,70 @@
+use crate::process::ProcessInfo;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// every checked port is free
+pub const EXIT_ALL_FREE: i32 = 0;
+/// at least one checked port is in use
+pub const EXIT_IN_USE: i32 = 1;
+/// the ports could not be checked
+pub const EXIT_ERROR: i32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortStatus {
+    pub port: u16,
+    pub in_use: bool,
+    pub pid: Option<u32>,
+    pub name: Option<String>,
+    pub command: Option<String>,
+}
+
+impl fmt::Display for PortStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pid {
+            None => write!(f, "{:<5}  free", self.port),
+            Some(pid) => {
+                write!(
+                    f,
+                    "{:<5}  in use by {}[{pid}]",
+                    self.port,
+                    self.name.as_deref().unwrap_or("?")
+                )?;
+                match self.command.as_deref() {
+                    Some(cmd) if !cmd.is_empty() => write!(f, "  {cmd}"),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// look up the owner of each port
+pub fn check_ports(
+    ports: &[u16],
+    port_map: &HashMap<u16, u32>,
+    processes: &[ProcessInfo],
+) -> Vec<PortStatus> {
+    ports
+        .iter()
+        .map(|&port| {
+            let pid = port_map.get(&port).copied();
+            let owner = pid.and_then(|pid| processes.iter().find(|p| p.pid == pid as i32));
+            PortStatus {
+                port,
+                in_use: pid.is_some(),
+                pid,
+                name: owner.map(|p| p.name.clone()),
+                command: owner.map(|p| p.cmd.join(" ")),
+            }
+        })
+        .collect()
+}
+
+pub fn exit_code(statuses: &[PortStatus]) -> i32 {
+    if statuses.iter().any(|s| s.in_use) {
+        EXIT_IN_USE
+    } else {
+        EXIT_ALL_FREE
+    }
+}
@@ -1,21 +1,468 @@
-use clap::Parser;
+use crate::columns::Column;
+use crate::config::Config;
+use crate::freeport::PortRange;
+use crate::output::OutputFormat;
+use crate::theme::ColorChoice;
+use anyhow::{anyhow, bail, Context};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// PortSage CLI definition
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// When to use colors; NO_COLOR turns them off in auto mode
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::default())]
+    pub color: ColorChoice,
+
+    // deprecated flags from before subcommands existed, kept as hidden aliases
+    #[arg(short, long, hide = true)]
+    pub filter: Option<String>,
+
+    #[arg(short, long, hide = true)]
+    pub port: Option<u16>,
+
+    #[arg(long, hide = true, conflicts_with = "tui")]
+    pub json: bool,
+
+    #[arg(long, hide = true)]
+    pub kill: Option<u32>,
+
+    #[arg(long, hide = true, conflicts_with = "tui")]
+    pub cli: bool,
+
+    #[arg(long, hide = true)]
+    pub tui: bool,
+
+    #[arg(long, hide = true)]
+    pub listening: bool,
+
+    #[arg(long, hide = true)]
+    pub preset: Option<String>,
+}
+
+impl Cli {
+    /// the subcommand to run, translating deprecated flags when none was given
+    pub fn into_command(self) -> Command {
+        if let Some(command) = self.command {
+            return command;
+        }
+
+        let select = SelectArgs {
+            filter: self.filter,
+            listening: self.listening,
+            all: false,
+            preset: self.preset,
+            from: None,
+        };
+        if let Some(pid) = self.kill {
+            Command::Kill(KillArgs {
+                pid: Some(pid),
+                port: None,
+                signal: default_signal(),
+            })
+        } else if self.cli || self.json {
+            // `--json` only ever meant list output, so it implies `--cli`
+            Command::List(ListArgs {
+                select,
+                port: self.port,
+                json: self.json,
+                ..ListArgs::default()
+            })
+        } else {
+            // the TUI has no port option, but its filter does the same
+            let filter = select
+                .filter
+                .or_else(|| self.port.map(|port| format!("port:{port}")));
+            Command::Tui(TuiArgs {
+                select: SelectArgs { filter, ..select },
+                ..TuiArgs::default()
+            })
+        }
+    }
+
+    /// reject deprecated flags that would otherwise be ignored: those given
+    /// with a subcommand, and `--port` with `--filter` when they open the TUI
+    pub fn check_deprecated_flags(&self) -> Result<(), clap::Error> {
+        let conflict = |message: String| Cli::command().error(ErrorKind::ArgumentConflict, message);
+        if self.command.is_some() {
+            if let Some((flag, _)) = self.deprecated_flags().first() {
+                return Err(conflict(format!(
+                    "`{flag}` cannot be used with a subcommand; pass the subcommand's own options after it"
+                )));
+            }
+        }
+        if self.opens_tui() && self.port.is_some() && self.filter.is_some() {
+            return Err(conflict(
+                "`--port` and `--filter` need `--cli`, or use `portsage list --filter <FILTER> --port <PORT>`"
+                    .into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// whether the deprecated flags open the TUI, as they do without `--cli`, `--json` or `--kill`
+    fn opens_tui(&self) -> bool {
+        !self.cli && !self.json && self.kill.is_none()
+    }
+
+    /// deprecated flags that were passed, with the replacement to suggest
+    pub fn deprecated_flags(&self) -> Vec<(&'static str, &'static str)> {
+        // the same flags select processes for the TUI unless `--cli` is given
+        let tui = self.opens_tui();
+        let select = |list, tui_instead| if tui { tui_instead } else { list };
+        let used = [
+            (self.cli, "--cli", "portsage list"),
+            (self.tui, "--tui", "portsage tui"),
+            (self.kill.is_some(), "--kill", "portsage kill <PID>"),
+            (
+                self.filter.is_some(),
+                "--filter",
+                select("portsage list --filter", "portsage tui --filter"),
+            ),
+            (
+                self.port.is_some(),
+                "--port",
+                select("portsage list --port", "portsage tui --filter port:<PORT>"),
+            ),
+            (self.json, "--json", "portsage list --json"),
+            (
+                self.listening,
+                "--listening",
+                select("portsage list --listening", "portsage tui --listening"),
+            ),
+            (
+                self.preset.is_some(),
+                "--preset",
+                select("portsage list --preset", "portsage tui --preset"),
+            ),
+        ];
+        used.into_iter()
+            .filter(|(on, _, _)| *on)
+            .map(|(_, flag, instead)| (flag, instead))
+            .collect()
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Browse processes interactively (default)
+    Tui(TuiArgs),
+    /// Print processes and their ports
+    List(ListArgs),
+    /// Send a signal to a process by PID or by the port it listens on
+    Kill(KillArgs),
+    /// Show processes as a parent/child tree
+    Tree(TreeArgs),
+    /// Show who owns each port; exits 0 if all are free, 1 if any is in use, 2 on error
+    Check(CheckArgs),
+    /// Find ports that nothing is listening on
+    FreePort(FreePortArgs),
+    /// Block until a port is listening, or released with --free
+    Wait(WaitArgs),
+    /// Print listeners as they appear (+) and disappear (-)
+    Watch(WatchArgs),
+    /// Save the process and port state to a JSON file
+    Snapshot(SnapshotArgs),
+    /// Compare two snapshots, or a snapshot with the live system
+    Diff(DiffArgs),
+    /// Compare listeners against the expected ports in a policy file
+    Audit(AuditArgs),
+    /// Check the config file or print the default one
+    Config(ConfigArgs),
+}
+
+/// options shared by commands that select processes
+#[derive(Args, Debug, Default, Clone)]
+pub struct SelectArgs {
+    /// Filter by PID, name, command or port; `port:N` only matches port N
     #[arg(short, long)]
     pub filter: Option<String>,
 
+    /// Only show processes that own listening ports
+    #[arg(long)]
+    pub listening: bool,
+
+    /// Show every process, even when the config file sets `listening = true`
+    #[arg(long, conflicts_with = "listening")]
+    pub all: bool,
+
+    /// Apply a named filter preset from the config file
+    #[arg(long, conflicts_with = "filter")]
+    pub preset: Option<String>,
+
+    /// Read processes from a snapshot file instead of the live system
+    #[arg(long, value_name = "FILE")]
+    pub from: Option<PathBuf>,
+}
+
+impl SelectArgs {
+    /// the one filter that applies: `--filter`, else the preset named by
+    /// `--preset`, else `preset` in `[defaults]`
+    pub fn query(&self, config: &Config) -> anyhow::Result<Option<String>> {
+        if let Some(ref filter) = self.filter {
+            return Ok(Some(filter.clone()));
+        }
+        match self.preset.as_ref().or(config.defaults.preset.as_ref()) {
+            Some(name) => Ok(Some(
+                config
+                    .preset(name)
+                    .ok_or_else(|| anyhow!("unknown preset `{name}`"))?
+                    .filter
+                    .clone(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// whether `--listening` applies, from the flags or `[defaults]`
+    pub fn listening_only(&self, config: &Config) -> bool {
+        self.listening || (config.defaults.listening && !self.all)
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TuiArgs {
+    #[command(flatten)]
+    pub select: SelectArgs,
+
+    /// Columns to show, e.g. pid,name,user,ports,cpu,mem,cwd,start,cmd
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Option<Vec<Column>>,
+
+    /// How often to reload processes, e.g. 500ms or 5s; 0 turns it off [default: 2s]
+    #[arg(long, value_parser = parse_duration)]
+    pub refresh: Option<Duration>,
+
+    /// Color theme: dark, light, high-contrast, monochrome or one from the config file
+    #[arg(long)]
+    pub theme: Option<String>,
+
+    /// Leave the mouse to the terminal, e.g. to select text
+    #[arg(long)]
+    pub no_mouse: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub select: SelectArgs,
+
+    /// Only show the process listening on this port
     #[arg(short, long)]
     pub port: Option<u16>,
 
+    /// Output format [default: table]
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Same as --format json
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Omit the header row
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// Columns to show, e.g. pid,name,user,ports,cpu,mem,cwd,start,cmd
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Option<Vec<Column>>,
+
+    /// Print each process with a template, e.g. '{pid}\t{name}\t{ports}\t{cwd}'
+    #[arg(long, conflicts_with_all = ["format", "json", "columns"])]
+    pub template: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct KillArgs {
+    #[arg(required_unless_present = "port", conflicts_with = "port")]
+    pub pid: Option<u32>,
+
+    /// Kill the process listening on this port
+    #[arg(short, long)]
+    pub port: Option<u16>,
+
+    /// Signal to send, e.g. TERM, INT or KILL
+    #[arg(short, long, default_value_t = default_signal())]
+    pub signal: String,
+}
+
+fn default_signal() -> String {
+    "KILL".to_string()
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TreeArgs {
+    #[command(flatten)]
+    pub select: SelectArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    #[arg(required = true)]
+    pub ports: Vec<u16>,
+
     #[arg(long)]
     pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct FreePortArgs {
+    /// Port range to search, e.g. 3000-9999
+    #[arg(default_value_t = PortRange::default())]
+    pub range: PortRange,
+
+    /// Number of ports to return
+    #[arg(short = 'n', long, default_value_t = 1)]
+    pub count: usize,
+
+    /// Double-check each port by binding to it, on 127.0.0.1 or `--verify=ADDR`
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "127.0.0.1"
+    )]
+    pub verify: Option<IpAddr>,
 
     #[arg(long)]
-    pub kill: Option<u32>,
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct WaitArgs {
+    #[arg(short, long)]
+    pub port: u16,
+
+    /// Give up after this long (e.g. 500ms, 30s, 2m); 0 waits forever
+    #[arg(long, default_value = "30s", value_parser = parse_duration)]
+    pub timeout: Duration,
 
+    /// How often to check the port
+    #[arg(long, default_value = "250ms", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// Wait until something listens on the port (default)
+    #[arg(long, conflicts_with = "free")]
+    pub listening: bool,
+
+    /// Wait until nothing listens on the port
     #[arg(long)]
-    pub cli: bool,
+    pub free: bool,
+
+    /// Print the PID that took the port
+    #[arg(long)]
+    pub pid: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// How often to poll
+    #[arg(long, default_value = "1s", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// Only report these ports
+    #[arg(short, long = "port", value_name = "PORT")]
+    pub ports: Vec<u16>,
+
+    /// Report the listeners that already exist when starting
+    #[arg(long)]
+    pub initial: bool,
+
+    /// Print one JSON object per line
+    #[arg(long)]
+    pub json: bool,
+
+    /// Run the [[hooks]] from the config file on each event
+    #[arg(long)]
+    pub hooks: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+    /// File to write; prints to stdout when omitted
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Snapshot to compare from
+    pub before: PathBuf,
+
+    /// Snapshot to compare to; the live system when omitted
+    pub after: Option<PathBuf>,
+
+    /// Ignore processes that started or exited without owning ports
+    #[arg(long)]
+    pub listeners_only: bool,
+
+    /// Exit with 1 when there are differences
+    #[arg(long)]
+    pub exit_code: bool,
+
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    /// Policy file listing the allowed listeners
+    #[arg(long, value_name = "FILE", default_value = "portsage.toml")]
+    pub policy: PathBuf,
+
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    /// Print a config file with every default value
+    #[arg(long, conflicts_with = "path")]
+    pub print_default: bool,
+
+    /// Print where the config file is read from
+    #[arg(long)]
+    pub path: bool,
+}
+
+/// parse durations like `500ms`, `30s`, `2m` or `1h`; a bare number is seconds
+pub fn parse_duration(s: &str) -> anyhow::Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .with_context(|| format!("invalid duration `{s}`"))?;
+
+    let seconds = |per_unit: u64| {
+        value
+            .checked_mul(per_unit)
+            .map(Duration::from_secs)
+            .with_context(|| format!("duration `{s}` is too long"))
+    };
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "m" => seconds(60),
+        "h" => seconds(3600),
+        _ => bail!("invalid duration unit `{unit}` (use ms, s, m or h)"),
+    }
+}
+
+/// like [`parse_duration`], but a polling interval of zero would spin, so it is rejected
+pub fn parse_interval(s: &str) -> anyhow::Result<Duration> {
+    let interval = parse_duration(s)?;
+    if interval.is_zero() {
+        bail!("interval `{s}` must be longer than zero");
+    }
+    Ok(interval)
 }
@@ -0,0 +1,151 @@
+use crate::format::{format_bytes, format_timestamp, join_ports};
+use crate::process::ProcessInfo;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// columns shown by `list` and the TUI table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Pid,
+    Name,
+    User,
+    Ports,
+    Cpu,
+    #[serde(alias = "memory")]
+    Mem,
+    Cwd,
+    Start,
+    #[serde(alias = "command")]
+    Cmd,
+}
+
+impl Column {
+    pub const ALL: [Column; 9] = [
+        Column::Pid,
+        Column::Name,
+        Column::User,
+        Column::Ports,
+        Column::Cpu,
+        Column::Mem,
+        Column::Cwd,
+        Column::Start,
+        Column::Cmd,
+    ];
+
+    pub const DEFAULT: [Column; 4] = [Column::Pid, Column::Name, Column::Ports, Column::Cmd];
+
+    /// the name used by `--columns` and the config file
+    pub fn key(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::Name => "name",
+            Column::User => "user",
+            Column::Ports => "ports",
+            Column::Cpu => "cpu",
+            Column::Mem => "mem",
+            Column::Cwd => "cwd",
+            Column::Start => "start",
+            Column::Cmd => "cmd",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "Name",
+            Column::User => "User",
+            Column::Ports => "Ports",
+            Column::Cpu => "CPU%",
+            Column::Mem => "Memory",
+            Column::Cwd => "CWD",
+            Column::Start => "Started",
+            Column::Cmd => "Command",
+        }
+    }
+
+    /// fixed width in the TUI table, `None` for columns that take the remaining space
+    pub fn width(self) -> Option<u16> {
+        match self {
+            Column::Pid => Some(8),
+            Column::Name => Some(20),
+            Column::User => Some(12),
+            Column::Ports => Some(10),
+            Column::Cpu => Some(6),
+            Column::Mem => Some(10),
+            Column::Start => Some(19),
+            Column::Cwd | Column::Cmd => None,
+        }
+    }
+
+    /// order two processes by this column, numerically where it makes sense
+    pub fn compare(self, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        match self {
+            Column::Pid => a.pid.cmp(&b.pid),
+            Column::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Column::User => a.user.cmp(&b.user),
+            Column::Ports => a.ports.first().cmp(&b.ports.first()),
+            Column::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            Column::Mem => a.memory.cmp(&b.memory),
+            Column::Cwd => a.cwd.cmp(&b.cwd),
+            Column::Start => a.start_time.cmp(&b.start_time),
+            Column::Cmd => a.cmd.cmp(&b.cmd),
+        }
+    }
+
+    pub fn value(self, p: &ProcessInfo) -> String {
+        match self {
+            Column::Pid => p.pid.to_string(),
+            Column::Name => p.name.clone(),
+            Column::User => p.user.clone(),
+            Column::Ports => join_ports(&p.ports),
+            Column::Cpu => format!("{:.1}", p.cpu_usage),
+            Column::Mem => format_bytes(p.memory),
+            Column::Cwd => p.cwd.clone(),
+            Column::Start => format_timestamp(p.start_time),
+            Column::Cmd => p.cmd.join(" "),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let column = match s.as_str() {
+            "command" => Column::Cmd,
+            "memory" => Column::Mem,
+            _ => match Column::ALL.into_iter().find(|c| c.key() == s) {
+                Some(column) => column,
+                None => bail!(
+                    "unknown column `{s}` (expected one of: {})",
+                    Column::ALL.map(Column::key).join(", ")
+                ),
+            },
+        };
+        Ok(column)
+    }
+}
+
+/// header titles and cell values for the given columns
+pub fn column_rows(
+    processes: &[ProcessInfo],
+    columns: &[Column],
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let headers = columns.iter().map(|c| c.title().to_string()).collect();
+    let rows = processes
+        .iter()
+        .map(|p| columns.iter().map(|c| c.value(p)).collect())
+        .collect();
+    (headers, rows)
+}
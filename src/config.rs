@@ -0,0 +1,359 @@
+use crate::bindings::{format_key, KeyBindings};
+use crate::cli::parse_duration;
+use crate::columns::Column;
+use crate::hooks::Hook;
+use crate::output::OutputFormat;
+use crate::theme::{format_color, ColorOverrides, Theme, BUILTIN_THEMES};
+use anyhow::{anyhow, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// how often the TUI reloads processes unless configured otherwise
+pub const DEFAULT_REFRESH: Duration = Duration::from_secs(2);
+
+/// where the keys of the config text are, so that errors found after parsing
+/// point at a line like the errors of the parser do
+struct Lines<'a> {
+    text: &'a str,
+    doc: Option<toml_edit::ImDocument<&'a str>>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            doc: toml_edit::ImDocument::parse(text).ok(),
+        }
+    }
+
+    /// the line of the key at `path`; entries of an array are picked by index
+    fn line(&self, path: &[&str]) -> Option<usize> {
+        let mut table: &dyn toml_edit::TableLike = self.doc.as_ref()?.as_table();
+        let mut span = None;
+        let mut parts = path.iter();
+        while let Some(part) = parts.next() {
+            let (key, item) = table.get_key_value(part)?;
+            span = key.span();
+            let index = parts
+                .as_slice()
+                .first()
+                .and_then(|i| i.parse::<usize>().ok());
+            table = match (item, index) {
+                (toml_edit::Item::ArrayOfTables(tables), Some(i)) => {
+                    parts.next();
+                    let entry = tables.get(i)?;
+                    span = entry.span().or(span);
+                    entry
+                }
+                (toml_edit::Item::Value(toml_edit::Value::Array(values)), Some(i)) => {
+                    parts.next();
+                    let entry = values.get(i)?;
+                    span = entry.span().or(span);
+                    entry.as_inline_table()?
+                }
+                _ if parts.as_slice().is_empty() => break,
+                _ => item.as_table_like()?,
+            };
+        }
+        let start = span?.start;
+        Some(self.text[..start].matches('\n').count() + 1)
+    }
+
+    /// `message` with the line of `path` in front, when the path is in the file
+    fn error(&self, path: &[&str], message: impl std::fmt::Display) -> anyhow::Error {
+        match self.line(path) {
+            Some(line) => anyhow!("line {line}: {message}"),
+            None => anyhow!("{message}"),
+        }
+    }
+}
+
+/// user configuration read from `$XDG_CONFIG_HOME/portsage/config.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub presets: BTreeMap<String, Preset>,
+    /// columns shown by `list` and the TUI
+    pub columns: Option<Vec<Column>>,
+    /// commands run by `watch --hooks`
+    pub hooks: Vec<Hook>,
+    /// how often the TUI reloads processes; `0` turns reloading off
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub refresh: Option<Duration>,
+    pub defaults: Defaults,
+    pub keys: KeyBindings,
+    /// a built-in theme or one from `themes`
+    pub theme: Option<String>,
+    /// custom themes, starting from the dark theme
+    pub themes: BTreeMap<String, ColorOverrides>,
+    /// colors replaced on top of the theme
+    pub colors: ColorOverrides,
+    /// whether the TUI captures the mouse; on unless set to false
+    pub mouse: Option<bool>,
+}
+
+/// values used when the matching command line flag is not given
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Defaults {
+    /// like `--listening`
+    pub listening: bool,
+    /// like `--preset`
+    pub preset: Option<String>,
+    /// like `list --format`
+    pub format: Option<OutputFormat>,
+}
+
+/// a named filter that can be recalled with `--preset` or a key in the TUI
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    pub filter: String,
+    #[serde(default)]
+    pub key: Option<char>,
+}
+
+impl Config {
+    /// load the config file, falling back to defaults when it does not exist
+    pub fn load() -> Result<Self> {
+        match config_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        let lines = Lines::new(text);
+        config.check_key_bindings(&lines)?;
+        config.check_presets(&lines)?;
+        for (i, hook) in config.hooks.iter().enumerate() {
+            hook.check()
+                .map_err(|e| lines.error(&["hooks", &i.to_string()], e))?;
+        }
+        Ok(config)
+    }
+
+    /// the TUI runs the first action bound to a key, so a key shared by two
+    /// actions would leave the later one unreachable
+    fn check_key_bindings(&self, lines: &Lines) -> Result<()> {
+        let mut seen: Vec<(KeyEvent, &str)> = Vec::new();
+        for (action, _, keys) in self.keys.actions() {
+            for key in keys {
+                if let Some((_, other)) = seen.iter().find(|(k, a)| k == key && *a != action) {
+                    // one of the two is set in the file, the other may be a default
+                    let at = if lines.line(&["keys", action]).is_some() {
+                        action
+                    } else {
+                        other
+                    };
+                    return Err(lines.error(
+                        &["keys", at],
+                        format!(
+                            "[keys]: `{}` is bound to both `{other}` and `{action}`",
+                            format_key(key)
+                        ),
+                    ));
+                }
+                seen.push((*key, action));
+            }
+        }
+        Ok(())
+    }
+
+    /// `defaults.preset` must name a preset, and the TUI only looks at preset
+    /// keys no binding takes, so a clash would leave the preset unreachable
+    fn check_presets(&self, lines: &Lines) -> Result<()> {
+        if let Some(ref name) = self.defaults.preset {
+            if !self.presets.contains_key(name) {
+                return Err(lines.error(
+                    &["defaults", "preset"],
+                    format!("[defaults]: unknown preset `{name}`"),
+                ));
+            }
+        }
+
+        let mut seen: BTreeMap<char, &str> = BTreeMap::new();
+        for (name, preset) in &self.presets {
+            let Some(key) = preset.key else {
+                continue;
+            };
+            let error = |message: String| lines.error(&["presets", name, "key"], message);
+            if key == 'g' {
+                return Err(error(format!(
+                    "preset `{name}`: key `g` is taken by `g g` (go to the first row)"
+                )));
+            }
+            let bound = [KeyModifiers::NONE, KeyModifiers::SHIFT]
+                .into_iter()
+                .find_map(|m| self.keys.action_for(&KeyEvent::new(KeyCode::Char(key), m)));
+            if let Some(action) = bound {
+                return Err(error(format!(
+                    "preset `{name}`: key `{key}` is already bound to `{action}` in [keys]"
+                )));
+            }
+            if let Some(other) = seen.insert(key, name) {
+                return Err(error(format!(
+                    "presets `{other}` and `{name}` both use key `{key}`"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// resolve the theme named by `name`, or by `theme` in the config file,
+    /// and apply `[colors]` on top of it
+    pub fn theme(&self, name: Option<&str>) -> Result<Theme> {
+        let theme = match name.or(self.theme.as_deref()) {
+            None => Theme::default(),
+            Some(name) => self
+                .themes
+                .get(name)
+                .map(|custom| Theme::default().with(custom))
+                .or_else(|| Theme::builtin(name))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "unknown theme `{name}`, expected one of {} or a [themes.{name}] table",
+                        BUILTIN_THEMES.join(", ")
+                    )
+                })?,
+        };
+        Ok(theme.with(&self.colors))
+    }
+
+    pub fn preset(&self, name: &str) -> Option<&Preset> {
+        self.presets.get(name)
+    }
+
+    /// find the preset bound to a key in the TUI
+    pub fn preset_for_key(&self, key: char) -> Option<(&str, &Preset)> {
+        self.presets
+            .iter()
+            .find(|(_, p)| p.key == Some(key))
+            .map(|(name, p)| (name.as_str(), p))
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse_duration(&text)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// a config file spelling out every default, for `portsage config --print-default`
+pub fn default_config() -> String {
+    let mut text = String::from(
+        "# portsage configuration\n\
+         # save as $XDG_CONFIG_HOME/portsage/config.toml (usually ~/.config/portsage/config.toml)\n\n\
+         # how often the TUI reloads processes, e.g. \"500ms\" or \"5s\"; \"0\" turns it off\n",
+    );
+    let _ = writeln!(text, "refresh = \"{}s\"", DEFAULT_REFRESH.as_secs());
+
+    let columns: Vec<String> = Column::DEFAULT
+        .iter()
+        .map(|c| format!("\"{}\"", c.key()))
+        .collect();
+    let _ = writeln!(text, "columns = [{}]", columns.join(", "));
+    let _ = writeln!(
+        text,
+        "\n# {}, or a custom theme from [themes.<name>]\ntheme = \"dark\"",
+        BUILTIN_THEMES.join(", ")
+    );
+    text.push_str(
+        "\n# clicks and the wheel in the TUI; false leaves the mouse to the terminal\nmouse = true\n",
+    );
+
+    text.push_str(
+        "\n[defaults]\n\
+         listening = false\n\
+         # preset = \"db\"\n\
+         format = \"table\"\n\
+         \n\
+         # a key or a list of keys, e.g. \"ctrl-k\", \"shift-tab\", \"pagedown\", \"f5\"\n\
+         [keys]\n",
+    );
+    let keys = KeyBindings::default();
+    for (action, _, events) in keys.actions() {
+        let events: Vec<String> = events
+            .iter()
+            .map(|k| format!("\"{}\"", format_key(k)))
+            .collect();
+        let _ = writeln!(text, "{action} = [{}]", events.join(", "));
+    }
+
+    text.push_str(
+        "\n# colors replaced on top of the theme: color names, 0-255 or #rrggbb\n\
+         # the values below are those of the dark theme\n[colors]\n",
+    );
+    for (slot, color) in Theme::default().entries() {
+        let _ = writeln!(text, "# {slot} = \"{}\"", format_color(color));
+    }
+
+    text.push_str(
+        "\n# [presets.db]\n\
+         # filter = \"port:5432\"\n\
+         # key = \"1\"\n",
+    );
+    text
+}
+
+/// store the column selection in the config file, keeping the rest of it intact
+pub fn save_columns(columns: &[Column]) -> Result<()> {
+    let path = config_path().context("cannot locate the config directory")?;
+    save_columns_to(&path, columns)
+}
+
+pub fn save_columns_to(path: &Path, columns: &[Column]) -> Result<()> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .with_context(|| format!("invalid config file {}", path.display()))?;
+
+    let keys: toml_edit::Array = columns.iter().map(|c| c.key()).collect();
+    doc["columns"] = toml_edit::value(keys);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, doc.to_string()).with_context(|| format!("failed to write {}", path.display()))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("config.toml"))
+}
+
+/// directory for data kept between sessions, such as filter history
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))
+        .map(|dir| dir.join("portsage"))
+}
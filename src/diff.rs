@@ -0,0 +1,211 @@
+use crate::process::ProcessInfo;
+use crate::watch::{listeners, Listener};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+/// `diff --exit-code` found differences; a snapshot that cannot be read
+/// exits with `check::EXIT_ERROR` instead
+pub const EXIT_CHANGED: i32 = 1;
+
+/// what changed between two process lists
+#[derive(Debug, Default, Serialize)]
+pub struct StateDiff {
+    pub listeners_added: Vec<ProcessRef>,
+    pub listeners_removed: Vec<ProcessRef>,
+    pub ports_moved: Vec<PortMove>,
+    pub processes_started: Vec<ProcessRef>,
+    pub processes_exited: Vec<ProcessRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProcessRef {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    pub pid: i32,
+    pub name: String,
+    /// whether the process owned ports, for `listeners_only`
+    #[serde(skip)]
+    pub held_ports: bool,
+}
+
+/// a port that is held by different processes afterwards
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortMove {
+    pub port: u16,
+    pub from: Vec<ProcessRef>,
+    pub to: Vec<ProcessRef>,
+}
+
+impl From<&Listener> for ProcessRef {
+    fn from(l: &Listener) -> Self {
+        Self {
+            port: Some(l.port),
+            pid: l.pid,
+            name: l.name.clone(),
+            held_ports: true,
+        }
+    }
+}
+
+impl From<&ProcessInfo> for ProcessRef {
+    fn from(p: &ProcessInfo) -> Self {
+        Self {
+            port: None,
+            pid: p.pid,
+            name: p.name.clone(),
+            held_ports: p.is_listening(),
+        }
+    }
+}
+
+impl fmt::Display for ProcessRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.port {
+            Some(port) => write!(f, "{port} {}[{}]", self.name, self.pid),
+            None => write!(f, "{}[{}]", self.name, self.pid),
+        }
+    }
+}
+
+pub fn diff_states(before: &[ProcessInfo], after: &[ProcessInfo]) -> StateDiff {
+    let before_listeners = listeners(before);
+    let after_listeners = listeners(after);
+
+    let holders = |map: &BTreeMap<(u16, i32), Listener>| {
+        let mut by_port: BTreeMap<u16, Vec<Listener>> = BTreeMap::new();
+        for l in map.values() {
+            by_port.entry(l.port).or_default().push(l.clone());
+        }
+        by_port
+    };
+    let before_ports = holders(&before_listeners);
+    let after_ports = holders(&after_listeners);
+
+    let mut diff = StateDiff::default();
+    let all_ports: BTreeSet<u16> = before_ports
+        .keys()
+        .chain(after_ports.keys())
+        .copied()
+        .collect();
+    for port in all_ports {
+        let old = before_ports
+            .get(&port)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let new = after_ports
+            .get(&port)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let gone: Vec<ProcessRef> = old
+            .iter()
+            .filter(|l| !after_listeners.contains_key(&(port, l.pid)))
+            .map(ProcessRef::from)
+            .collect();
+        let came: Vec<ProcessRef> = new
+            .iter()
+            .filter(|l| !before_listeners.contains_key(&(port, l.pid)))
+            .map(ProcessRef::from)
+            .collect();
+
+        // a port only moved when one holder replaced another; a holder that
+        // joins or leaves next to one that stays is an addition or removal
+        if gone.is_empty() || came.is_empty() {
+            diff.listeners_added.extend(came);
+            diff.listeners_removed.extend(gone);
+        } else {
+            diff.ports_moved.push(PortMove {
+                port,
+                from: gone,
+                to: came,
+            });
+        }
+    }
+
+    let identity = |p: &ProcessInfo| (p.pid, p.start_time);
+    let before_ids: HashSet<_> = before.iter().map(identity).collect();
+    let after_ids: HashSet<_> = after.iter().map(identity).collect();
+    diff.processes_started =
+        sorted_refs(after.iter().filter(|p| !before_ids.contains(&identity(p))));
+    diff.processes_exited =
+        sorted_refs(before.iter().filter(|p| !after_ids.contains(&identity(p))));
+
+    diff
+}
+
+fn sorted_refs<'a>(processes: impl Iterator<Item = &'a ProcessInfo>) -> Vec<ProcessRef> {
+    let mut refs: Vec<ProcessRef> = processes.map(ProcessRef::from).collect();
+    refs.sort_by_key(|r| r.pid);
+    refs
+}
+
+impl StateDiff {
+    pub fn listeners_changed(&self) -> bool {
+        !(self.listeners_added.is_empty()
+            && self.listeners_removed.is_empty()
+            && self.ports_moved.is_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.listeners_changed()
+            && self.processes_started.is_empty()
+            && self.processes_exited.is_empty()
+    }
+
+    /// drop the processes that started or exited without owning ports
+    pub fn listeners_only(mut self) -> Self {
+        self.processes_started.retain(|p| p.held_ports);
+        self.processes_exited.retain(|p| p.held_ports);
+        self
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+
+        let sections: [(&str, &str, &[ProcessRef]); 4] = [
+            ("Listeners added", "+", &self.listeners_added),
+            ("Listeners removed", "-", &self.listeners_removed),
+            ("Processes started", "+", &self.processes_started),
+            ("Processes exited", "-", &self.processes_exited),
+        ];
+        for (title, sign, refs) in &sections[..2] {
+            write_section(f, title, sign, refs)?;
+        }
+        if !self.ports_moved.is_empty() {
+            writeln!(f, "Ports moved ({}):", self.ports_moved.len())?;
+            for m in &self.ports_moved {
+                let names = |refs: &[ProcessRef]| {
+                    refs.iter()
+                        .map(|r| format!("{}[{}]", r.name, r.pid))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                writeln!(f, "  ~ {} {} -> {}", m.port, names(&m.from), names(&m.to))?;
+            }
+        }
+        for (title, sign, refs) in &sections[2..] {
+            write_section(f, title, sign, refs)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_section(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    sign: &str,
+    refs: &[ProcessRef],
+) -> fmt::Result {
+    if refs.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{title} ({}):", refs.len())?;
+    for r in refs {
+        writeln!(f, "  {sign} {r}")?;
+    }
+    Ok(())
+}
@@ -15,3 +15,61 @@ pub fn filter_processes_by_name<'a>(
         })
         .collect()
 }
+
+/// filter by a query matched against PID, name, command line and ports.
+/// `port:<N>` only matches processes listening on port N.
+pub fn filter_processes<'a>(processes: &'a [ProcessInfo], query: &str) -> Vec<&'a ProcessInfo> {
+    processes
+        .iter()
+        .filter(|p| matches_query(p, query))
+        .collect()
+}
+
+pub fn matches_query(p: &ProcessInfo, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+
+    if let Some(port) = query.strip_prefix("port:") {
+        return port
+            .trim()
+            .parse::<u16>()
+            .is_ok_and(|port| p.ports.contains(&port));
+    }
+
+    p.pid.to_string().contains(&query)
+        || p.name.to_lowercase().contains(&query)
+        || p.cmd.join(" ").to_lowercase().contains(&query)
+        || p.ports.iter().any(|port| port.to_string().contains(&query))
+}
+
+/// match `text` against a shell-style pattern where `*` matches any run of
+/// characters and `?` matches exactly one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // let the last `*` swallow one more character
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
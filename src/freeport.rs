@@ -0,0 +1,66 @@
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, TcpListener};
+use std::str::FromStr;
+
+/// an inclusive range of ports, written as `3000-9999` or a single `8080`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl Default for PortRange {
+    fn default() -> Self {
+        Self {
+            start: 3000,
+            end: 9999,
+        }
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once('-').unwrap_or((s, s));
+        let start: u16 = start
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid port `{start}`"))?;
+        let end: u16 = end
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid port `{end}`"))?;
+        if start == 0 || start > end {
+            bail!("invalid port range `{s}`");
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// pick up to `count` ports in `range` that are not in the port map.
+/// when `bind_addr` is given each candidate is also checked by binding to it.
+pub fn find_free_ports(
+    range: PortRange,
+    count: usize,
+    port_map: &HashMap<u16, u32>,
+    bind_addr: Option<IpAddr>,
+) -> Vec<u16> {
+    (range.start..=range.end)
+        .filter(|port| !port_map.contains_key(port))
+        .filter(|port| bind_addr.iter().all(|addr| can_bind(*addr, *port)))
+        .take(count)
+        .collect()
+}
+
+pub fn can_bind(addr: IpAddr, port: u16) -> bool {
+    TcpListener::bind((addr, port)).is_ok()
+}
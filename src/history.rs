@@ -0,0 +1,117 @@
+use crate::config::state_dir;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const MAX_ENTRIES: usize = 100;
+
+/// filter history, oldest entry first, navigated like a shell history
+#[derive(Debug, Default)]
+pub struct FilterHistory {
+    entries: Vec<String>,
+    cursor: Option<usize>,
+    draft: String,
+    path: Option<PathBuf>,
+}
+
+impl FilterHistory {
+    pub fn new(entries: Vec<String>) -> Self {
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// load the history from the default location, starting empty on any error
+    pub fn load() -> Self {
+        match history_path() {
+            Some(path) => Self::load_from(&path),
+            None => Self::default(),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .map(|text| {
+                text.lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            path: Some(path.to_path_buf()),
+            ..Self::new(entries)
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut text = self.entries.join("\n");
+        text.push('\n');
+        fs::write(path, text)
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// record a filter as the newest entry, dropping any older duplicate
+    pub fn push(&mut self, entry: &str) {
+        self.reset();
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return;
+        }
+        self.entries.retain(|e| e != entry);
+        self.entries.push(entry.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// step to an older entry; `current` is kept so `newer` can return to it
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let index = match self.cursor {
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(i) => i.saturating_sub(1),
+        };
+        self.cursor = Some(index);
+        Some(&self.entries[index])
+    }
+
+    /// step to a newer entry, ending at the input that was being typed
+    pub fn newer(&mut self) -> Option<&str> {
+        let index = self.cursor?;
+        if index + 1 < self.entries.len() {
+            self.cursor = Some(index + 1);
+            Some(&self.entries[index + 1])
+        } else {
+            self.cursor = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// stop browsing, so the next `older` starts from the newest entry
+    pub fn reset(&mut self) {
+        self.cursor = None;
+        self.draft.clear();
+    }
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    state_dir().map(|d| d.join("history"))
+}
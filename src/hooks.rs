@@ -0,0 +1,121 @@
+use crate::filter::glob_match;
+use crate::process::ProcessInfo;
+use crate::watch::{ChangeKind, WatchEvent};
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use std::io;
+use std::process::{Child, Command};
+
+/// a shell command run when a port opens or closes, or a process exits
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hook {
+    pub on: HookTrigger,
+    /// ports to react to; empty means any port
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// process name pattern, `*` and `?` are wildcards
+    #[serde(default)]
+    pub process: Option<String>,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookTrigger {
+    Open,
+    Close,
+    Exit,
+}
+
+impl HookTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookTrigger::Open => "open",
+            HookTrigger::Close => "close",
+            HookTrigger::Exit => "exit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookEvent {
+    pub trigger: HookTrigger,
+    /// the port that opened or closed, or every port an exited process held
+    pub ports: Vec<u16>,
+    pub pid: i32,
+    pub name: String,
+    pub cwd: String,
+}
+
+impl From<&WatchEvent> for HookEvent {
+    fn from(e: &WatchEvent) -> Self {
+        Self {
+            trigger: match e.event {
+                ChangeKind::Open => HookTrigger::Open,
+                ChangeKind::Close => HookTrigger::Close,
+            },
+            ports: vec![e.port],
+            pid: e.pid,
+            name: e.name.clone(),
+            cwd: e.cwd.clone(),
+        }
+    }
+}
+
+impl HookEvent {
+    pub fn exited(p: &ProcessInfo) -> Self {
+        Self {
+            trigger: HookTrigger::Exit,
+            ports: p.ports.clone(),
+            pid: p.pid,
+            name: p.name.clone(),
+            cwd: p.cwd.clone(),
+        }
+    }
+
+    /// variables passed to hook commands
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let ports = self.ports.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        vec![
+            ("PORTSAGE_EVENT", self.trigger.as_str().to_string()),
+            ("PORTSAGE_PORT", ports.first().cloned().unwrap_or_default()),
+            ("PORTSAGE_PORTS", ports.join(",")),
+            ("PORTSAGE_PID", self.pid.to_string()),
+            ("PORTSAGE_NAME", self.name.clone()),
+            ("PORTSAGE_CWD", self.cwd.clone()),
+        ]
+    }
+}
+
+impl Hook {
+    /// an exit hook needs `process` or `ports`, or it would fire for every
+    /// process that exits on the machine
+    pub fn check(&self) -> Result<()> {
+        if self.on == HookTrigger::Exit && self.process.is_none() && self.ports.is_empty() {
+            bail!(
+                "the exit hook `{}` needs `process` or `ports` to pick the processes it is for",
+                self.command
+            );
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, event: &HookEvent) -> bool {
+        self.on == event.trigger
+            && (self.ports.is_empty() || event.ports.iter().any(|p| self.ports.contains(p)))
+            && self
+                .process
+                .iter()
+                .all(|pattern| glob_match(pattern, &event.name))
+    }
+
+    /// start the command through `sh -c` without waiting for it
+    pub fn spawn(&self, event: &HookEvent) -> io::Result<Child> {
+        Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .envs(event.env())
+            .spawn()
+    }
+}
@@ -1,6 +1,23 @@
+pub mod audit;
 pub mod bindings;
+pub mod check;
 pub mod cli;
+pub mod columns;
+pub mod config;
+pub mod diff;
 pub mod filter;
+pub mod format;
+pub mod freeport;
+pub mod history;
+pub mod hooks;
+pub mod output;
 pub mod port;
 pub mod process;
+pub mod procfs;
+pub mod snapshot;
+pub mod template;
+pub mod theme;
+pub mod tree;
 pub mod tui;
+pub mod wait;
+pub mod watch;
@@ -1,44 +1,475 @@
+use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
-use portsage::tui::run_tui;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
+use portsage::config::{config_path, default_config, Config, DEFAULT_REFRESH};
+use portsage::tui::{run_tui, TuiOptions};
 use portsage::{
-    cli::Cli, filter::filter_processes_by_name, port::get_port_pid_map, process::get_all_processes,
-    process::DisplayProcessInfo,
+    audit::{audit_processes, Policy, ViolationKind, EXIT_OK, EXIT_VIOLATIONS},
+    check::{check_ports, exit_code, EXIT_ERROR},
+    cli::{
+        AuditArgs, CheckArgs, Cli, Command, ConfigArgs, DiffArgs, FreePortArgs, KillArgs, ListArgs,
+        SelectArgs, SnapshotArgs, TreeArgs, TuiArgs, WaitArgs, WatchArgs,
+    },
+    columns::{column_rows, Column},
+    diff::{diff_states, EXIT_CHANGED},
+    filter::filter_processes,
+    freeport::find_free_ports,
+    hooks::HookEvent,
+    output::{render_rows, OutputFormat},
+    port::{try_get_bindings, try_get_port_pid_map},
+    process::{
+        get_all_processes, get_processes_with_bindings, get_processes_with_ports, ProcessInfo,
+    },
+    snapshot::Snapshot,
+    template::Template,
+    theme::{paint, ColorChoice, Theme},
+    tree::render_tree,
+    wait::{wait_for_port, WaitFor},
+    watch::{diff_listeners, exited_processes, listeners, ChangeKind},
 };
-use tabled::settings::Style;
-use tabled::Table;
+use ratatui::style::Color;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+use std::process::Child;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
     let cli = Cli::parse();
-    let processes = get_all_processes();
+    if let Err(e) = cli.check_deprecated_flags() {
+        e.exit();
+    }
+    for (flag, instead) in cli.deprecated_flags() {
+        eprintln!("warning: `{flag}` is deprecated, use `{instead}` instead");
+    }
+
+    let color = cli.color;
+    let paint_stdout = color.enabled(io::stdout().is_terminal());
+
+    let result = match cli.into_command() {
+        Command::Tui(args) => tui(&args, color),
+        Command::List(args) => list(&args),
+        Command::Kill(args) => kill(&args),
+        Command::Tree(args) => tree(&args),
+        Command::Check(args) => check(&args, paint_stdout),
+        Command::FreePort(args) => free_port(&args),
+        Command::Wait(args) => wait(&args),
+        Command::Watch(args) => watch(&args, paint_stdout),
+        Command::Snapshot(args) => snapshot(&args),
+        Command::Diff(args) => diff(&args, paint_stdout),
+        Command::Audit(args) => audit(&args, paint_stdout),
+        Command::Config(args) => config(&args),
+    };
+    // 1 means "in use", "changed" or "violations" for the commands that report
+    // those, so an error always exits with 2
+    if let Err(e) = result {
+        eprintln!("portsage: {e:#}");
+        std::process::exit(EXIT_ERROR);
+    }
+}
 
-    if !cli.cli {
-        run_tui(&processes).unwrap();
-        return;
+/// the processes on the live system; their ports come from `lsof`
+fn live_processes() -> Result<Vec<ProcessInfo>> {
+    get_all_processes().context("failed to run lsof")
+}
+
+/// read the processes from `--from` when given, otherwise from the live system
+fn load_processes(select: &SelectArgs) -> Result<Vec<ProcessInfo>> {
+    match select.from {
+        Some(ref path) => Ok(Snapshot::load(path)?.processes),
+        None => live_processes(),
     }
+}
 
-    let mut filtered = processes.clone();
+/// apply `--listening`, `--preset` and `--filter` to the process list
+fn select_processes(
+    processes: &[ProcessInfo],
+    select: &SelectArgs,
+    config: &Config,
+) -> Result<Vec<ProcessInfo>> {
+    let mut filtered = processes.to_vec();
 
-    if let Some(ref keyword) = cli.filter {
-        filtered = filter_processes_by_name(&filtered, keyword)
+    if select.listening_only(config) {
+        filtered.retain(|p| p.is_listening());
+    }
+
+    if let Some(ref query) = select.query(config)? {
+        filtered = filter_processes(&filtered, query)
             .into_iter()
             .cloned()
             .collect();
     }
 
-    if let Some(port) = cli.port {
-        let port_map = get_port_pid_map();
-        if let Some(&pid) = port_map.get(&port) {
-            filtered = filtered
-                .into_iter()
-                .filter(|p| p.pid == pid as i32)
-                .collect();
-        } else {
-            filtered.clear();
+    Ok(filtered)
+}
+
+fn tui(args: &TuiArgs, color: ColorChoice) -> Result<()> {
+    let config = Config::load()?;
+    let filter = args.select.query(&config)?;
+    let columns = args
+        .columns
+        .clone()
+        .or(config.columns.clone())
+        .unwrap_or_else(|| Column::DEFAULT.to_vec());
+    let (processes, snapshot) = match args.select.from {
+        Some(ref path) => {
+            let snapshot = Snapshot::load(path)?;
+            let label = snapshot.label();
+            (snapshot.processes, Some(label))
+        }
+        None => (live_processes()?, None),
+    };
+    let refresh = args.refresh.or(config.refresh).unwrap_or(DEFAULT_REFRESH);
+    let theme = if color.enabled(io::stdout().is_terminal()) {
+        config.theme(args.theme.as_deref())?
+    } else {
+        Theme::monochrome()
+    };
+
+    let stopped_by = run_tui(
+        &processes,
+        TuiOptions {
+            filter,
+            listening_only: args.select.listening_only(&config),
+            columns,
+            refresh,
+            theme,
+            snapshot,
+            mouse: !args.no_mouse && config.mouse.unwrap_or(true),
+            config,
+        },
+    )?;
+    // like a shell reports a process killed by a signal
+    if let Some(signal) = stopped_by {
+        std::process::exit(128 + signal);
+    }
+    Ok(())
+}
+
+fn list(args: &ListArgs) -> Result<()> {
+    let config = Config::load()?;
+    let processes = load_processes(&args.select)?;
+    let mut filtered = select_processes(&processes, &args.select, &config)?;
+
+    if let Some(port) = args.port {
+        filtered.retain(|p| p.ports.contains(&port));
+    }
+
+    if let Some(ref source) = args.template {
+        let template = Template::parse(source).context("invalid --template")?;
+        for p in &filtered {
+            println!("{}", template.render(p));
+        }
+        return Ok(());
+    }
+
+    let format = if args.json {
+        OutputFormat::Json
+    } else {
+        args.format.or(config.defaults.format).unwrap_or_default()
+    };
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&filtered)?);
+        return Ok(());
+    }
+
+    let columns = args
+        .columns
+        .clone()
+        .or(config.columns)
+        .unwrap_or_else(|| Column::DEFAULT.to_vec());
+    let (headers, rows) = column_rows(&filtered, &columns);
+    println!("{}", render_rows(&headers, &rows, format, !args.no_header));
+    Ok(())
+}
+
+fn kill(args: &KillArgs) -> Result<()> {
+    let signal = parse_signal(&args.signal)?;
+    let pid = match (args.pid, args.port) {
+        (Some(pid), _) => pid,
+        (None, Some(port)) => *try_get_port_pid_map()
+            .context("failed to run lsof")?
+            .get(&port)
+            .ok_or_else(|| anyhow!("nothing is listening on port {port}"))?,
+        (None, None) => bail!("a PID or --port is required"),
+    };
+
+    signal::kill(Pid::from_raw(pid as i32), signal)
+        .with_context(|| format!("failed to send {signal} to process {pid}"))?;
+    println!("✔ Sent {signal} to process {pid}");
+    Ok(())
+}
+
+/// accept `KILL`, `SIGKILL` or `9`
+fn parse_signal(name: &str) -> Result<Signal> {
+    if let Ok(number) = name.parse::<i32>() {
+        return Signal::try_from(number).with_context(|| format!("invalid signal `{name}`"));
+    }
+    let name = name.to_uppercase();
+    let name = if name.starts_with("SIG") {
+        name
+    } else {
+        format!("SIG{name}")
+    };
+    name.parse()
+        .with_context(|| format!("invalid signal `{name}`"))
+}
+
+fn tree(args: &TreeArgs) -> Result<()> {
+    let config = Config::load()?;
+    let processes = load_processes(&args.select)?;
+    let selected: HashSet<i32> = select_processes(&processes, &args.select, &config)?
+        .iter()
+        .map(|p| p.pid)
+        .collect();
+
+    print!("{}", render_tree(&processes, |p| selected.contains(&p.pid)));
+    Ok(())
+}
+
+fn free_port(args: &FreePortArgs) -> Result<()> {
+    let port_map = try_get_port_pid_map().context("failed to run lsof")?;
+    let ports = find_free_ports(args.range, args.count, &port_map, args.verify);
+
+    if args.json {
+        println!("{}", serde_json::json!({ "ports": ports }));
+    } else {
+        for port in &ports {
+            println!("{port}");
+        }
+    }
+
+    if ports.len() < args.count {
+        bail!(
+            "only {} of {} requested ports are free in {}",
+            ports.len(),
+            args.count,
+            args.range
+        );
+    }
+    Ok(())
+}
+
+fn wait(args: &WaitArgs) -> Result<()> {
+    let until = if args.free {
+        WaitFor::Free
+    } else {
+        WaitFor::Listening
+    };
+    let timeout = (!args.timeout.is_zero()).then_some(args.timeout);
+
+    let owner = wait_for_port(args.port, until, timeout, args.interval, |port| {
+        let port_map = try_get_port_pid_map().context("failed to run lsof")?;
+        Ok(port_map.get(&port).copied())
+    })?;
+
+    if let (true, Some(pid)) = (args.pid, owner) {
+        println!("{pid}");
+    }
+    Ok(())
+}
+
+fn check(args: &CheckArgs, color: bool) -> Result<()> {
+    let port_map = match try_get_port_pid_map() {
+        Ok(map) => map,
+        Err(e) => {
+            eprintln!("portsage: failed to run lsof: {e}");
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+    let processes = get_processes_with_ports(&port_map);
+    let statuses = check_ports(&args.ports, &port_map, &processes);
+
+    if args.json {
+        // exit code 1 means a port is in use, so errors must not end up there
+        match serde_json::to_string_pretty(&statuses) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("portsage: failed to write JSON: {e}");
+                std::process::exit(EXIT_ERROR);
+            }
         }
+    } else {
+        for status in &statuses {
+            let tint = if status.in_use {
+                Color::Red
+            } else {
+                Color::Green
+            };
+            println!("{}", paint(&status.to_string(), tint, color));
+        }
+    }
+
+    std::process::exit(exit_code(&statuses));
+}
+
+fn watch(args: &WatchArgs, color: bool) -> Result<()> {
+    let hooks = if args.hooks {
+        Config::load()?.hooks
+    } else {
+        Vec::new()
+    };
+    if args.hooks && hooks.is_empty() {
+        eprintln!("warning: no [[hooks]] are configured");
     }
 
-    let display_procs: Vec<DisplayProcessInfo> = filtered.iter().map(Into::into).collect();
-    let table = Table::new(display_procs).with(Style::rounded()).to_string();
+    let mut previous = if args.initial {
+        Vec::new()
+    } else {
+        live_processes()?
+    };
+    let mut running: Vec<Child> = Vec::new();
+
+    loop {
+        let current = live_processes()?;
+        let events = diff_listeners(&listeners(&previous), &listeners(&current), unix_now());
+
+        for event in events
+            .iter()
+            .filter(|e| args.ports.is_empty() || args.ports.contains(&e.port))
+        {
+            if args.json {
+                println!("{}", serde_json::to_string(event)?);
+            } else {
+                let line = event.to_string();
+                let tint = match event.event {
+                    ChangeKind::Open => Color::Green,
+                    ChangeKind::Close => Color::Red,
+                };
+                println!("{}", paint(&line, tint, color));
+            }
+        }
+
+        if !hooks.is_empty() {
+            let hook_events = events.iter().map(HookEvent::from).chain(
+                exited_processes(&previous, &current)
+                    .into_iter()
+                    .map(HookEvent::exited),
+            );
+            for event in hook_events {
+                for hook in hooks.iter().filter(|h| h.matches(&event)) {
+                    match hook.spawn(&event) {
+                        Ok(child) => running.push(child),
+                        Err(e) => eprintln!("warning: failed to run hook `{}`: {e}", hook.command),
+                    }
+                }
+            }
+            // reap finished hook commands
+            running.retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        }
+
+        previous = current;
+        std::thread::sleep(args.interval);
+    }
+}
+
+fn snapshot(args: &SnapshotArgs) -> Result<()> {
+    let snapshot = Snapshot::capture(live_processes()?);
+    match args.output {
+        Some(ref path) => {
+            snapshot.save(path)?;
+            eprintln!(
+                "✔ Saved {} processes to {}",
+                snapshot.processes.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", snapshot.to_json()?),
+    }
+    Ok(())
+}
+
+fn diff(args: &DiffArgs, color: bool) -> Result<()> {
+    let before = Snapshot::load(&args.before)?.processes;
+    let after = match args.after {
+        Some(ref path) => Snapshot::load(path)?.processes,
+        None => live_processes()?,
+    };
+
+    let mut diff = diff_states(&before, &after);
+    if args.listeners_only {
+        diff = diff.listeners_only();
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        for line in diff.to_string().lines() {
+            let tint = match line.trim_start().chars().next() {
+                Some('+') => Color::Green,
+                Some('-') => Color::Red,
+                Some('~') => Color::Yellow,
+                _ => Color::Reset,
+            };
+            println!("{}", paint(line, tint, color));
+        }
+    }
+
+    if args.exit_code && !diff.is_empty() {
+        std::process::exit(EXIT_CHANGED);
+    }
+    Ok(())
+}
+
+fn audit(args: &AuditArgs, color: bool) -> Result<()> {
+    let policy = match Policy::load(&args.policy) {
+        Ok(policy) => policy,
+        Err(e) => {
+            eprintln!("portsage: {e:#}");
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+    let bindings = match try_get_bindings() {
+        Ok(bindings) => bindings,
+        Err(e) => {
+            eprintln!("portsage: failed to run lsof: {e}");
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+    let violations = audit_processes(&policy, &get_processes_with_bindings(&bindings));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&violations)?);
+    } else if violations.is_empty() {
+        let message = format!("✔ All listeners match {}", args.policy.display());
+        println!("{}", paint(&message, Color::Green, color));
+    } else {
+        for violation in &violations {
+            let tint = match violation.kind {
+                ViolationKind::Missing => Color::Yellow,
+                _ => Color::Red,
+            };
+            println!("{}", paint(&violation.to_string(), tint, color));
+        }
+    }
+
+    std::process::exit(if violations.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_VIOLATIONS
+    });
+}
+
+fn config(args: &ConfigArgs) -> Result<()> {
+    if args.print_default {
+        print!("{}", default_config());
+        return Ok(());
+    }
+
+    let path = config_path().context("cannot locate the config directory")?;
+    if args.path {
+        println!("{}", path.display());
+    } else if path.exists() {
+        Config::load_from(&path)?;
+        println!("✔ {} is valid", path.display());
+    } else {
+        println!("{} does not exist; using defaults", path.display());
+    }
+    Ok(())
+}
 
-    println!("{table}");
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
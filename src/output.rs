@@ -0,0 +1,156 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+use tabled::builder::Builder;
+use tabled::settings::Style;
+
+/// output formats for `list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Rounded table
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Tsv,
+    Markdown,
+    /// Aligned columns without box drawing, for awk and cut
+    Plain,
+}
+
+/// render a header and rows in a text format.
+/// `Json` is not handled here since it serialises the full records instead.
+pub fn render_rows(
+    headers: &[String],
+    rows: &[Vec<String>],
+    format: OutputFormat,
+    header: bool,
+) -> String {
+    let headers = header.then_some(headers);
+    match format {
+        OutputFormat::Table | OutputFormat::Json => render_table(headers, rows),
+        OutputFormat::Csv => render_separated(headers, rows, ",", csv_escape),
+        OutputFormat::Tsv => render_separated(headers, rows, "\t", tsv_escape),
+        OutputFormat::Markdown => render_markdown(headers, rows),
+        OutputFormat::Plain => render_plain(headers, rows),
+    }
+}
+
+fn render_table(headers: Option<&[String]>, rows: &[Vec<String>]) -> String {
+    let mut builder = Builder::default();
+    if let Some(headers) = headers {
+        builder.push_record(headers.iter().cloned());
+    }
+    for row in rows {
+        builder.push_record(row.iter().cloned());
+    }
+    builder.build().with(Style::rounded()).to_string()
+}
+
+fn render_separated(
+    headers: Option<&[String]>,
+    rows: &[Vec<String>],
+    separator: &str,
+    escape: fn(&str) -> String,
+) -> String {
+    headers
+        .into_iter()
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|row| {
+            row.iter()
+                .map(|cell| escape(cell))
+                .collect::<Vec<_>>()
+                .join(separator)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn tsv_escape(cell: &str) -> String {
+    cell.replace(['\t', '\n', '\r'], " ")
+}
+
+fn render_markdown(headers: Option<&[String]>, rows: &[Vec<String>]) -> String {
+    let line = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|c| c.replace('|', "\\|").replace('\n', " "))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = Vec::new();
+    if let Some(headers) = headers {
+        lines.push(line(headers));
+        lines.push(format!("|{}", " --- |".repeat(headers.len())));
+    }
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+/// space-aligned columns. every field but the last is kept free of whitespace
+/// so awk sees a stable column count: lists lose their spaces (`80,443`),
+/// other spaces become `_` and empty fields print as `-`.
+fn render_plain(headers: Option<&[String]>, rows: &[Vec<String>]) -> String {
+    let cells: Vec<Vec<String>> = headers
+        .into_iter()
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|row| {
+            let last = row.len().saturating_sub(1);
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    if i == last {
+                        cell.replace('\n', " ")
+                    } else if cell.trim().is_empty() {
+                        "-".to_string()
+                    } else {
+                        cell.replace(", ", ",")
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join("_")
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|c| c.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    cells
+        .iter()
+        .map(|row| {
+            let last = row.len().saturating_sub(1);
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    if i == last {
+                        cell.clone()
+                    } else {
+                        format!("{cell:<width$}", width = widths[i])
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
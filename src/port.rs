@@ -1,9 +1,41 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::io;
 use std::process::Command;
 
-/// creathe a map of port to PID from lsof output
-pub fn parse_lsof_output(output: &str) -> HashMap<u16, u32> {
-    let mut map = HashMap::new();
+/// a listening socket: the local address and port it is bound to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Binding {
+    /// `*` for all interfaces, otherwise the address without brackets
+    pub addr: String,
+    pub port: u16,
+}
+
+impl Binding {
+    /// bound to every interface (`0.0.0.0` / `::`)
+    pub fn is_exposed(&self) -> bool {
+        matches!(self.addr.as_str(), "*" | "0.0.0.0" | "::")
+    }
+
+    pub fn is_loopback(&self) -> bool {
+        self.addr == "localhost" || self.addr == "::1" || self.addr.starts_with("127.")
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.addr.contains(':') {
+            write!(f, "[{}]:{}", self.addr, self.port)
+        } else {
+            write!(f, "{}:{}", self.addr, self.port)
+        }
+    }
+}
+
+/// parse lsof output into (PID, binding) pairs
+pub fn parse_lsof_bindings(output: &str) -> Vec<(u32, Binding)> {
+    let mut bindings = Vec::new();
 
     for line in output.lines().skip(1) {
         let cols: Vec<&str> = line.split_whitespace().collect();
@@ -13,23 +45,44 @@ pub fn parse_lsof_output(output: &str) -> HashMap<u16, u32> {
 
         let pid: u32 = cols[1].parse().unwrap_or(0);
         let nameport = cols[8];
-        if let Some(port_str) = nameport.split(':').last() {
+        if let Some((addr, port_str)) = nameport.rsplit_once(':') {
             if let Ok(port) = port_str.parse::<u16>() {
-                map.insert(port, pid);
+                let addr = addr.trim_start_matches('[').trim_end_matches(']');
+                let binding = Binding {
+                    addr: addr.to_string(),
+                    port,
+                };
+                if !bindings.contains(&(pid, binding.clone())) {
+                    bindings.push((pid, binding));
+                }
             }
         }
     }
 
-    map
+    bindings
+}
+
+/// creathe a map of port to PID from lsof output
+pub fn parse_lsof_output(output: &str) -> HashMap<u16, u32> {
+    port_pid_map(&parse_lsof_bindings(output))
+}
+
+/// collapse bindings into a map of port to PID
+pub fn port_pid_map(bindings: &[(u32, Binding)]) -> HashMap<u16, u32> {
+    bindings.iter().map(|(pid, b)| (b.port, *pid)).collect()
+}
+
+/// the port to PID map at runtime; fails when `lsof` cannot run
+pub fn try_get_port_pid_map() -> io::Result<HashMap<u16, u32>> {
+    Ok(port_pid_map(&try_get_bindings()?))
 }
 
-/// get the port to PID map at runtime
-pub fn get_port_pid_map() -> HashMap<u16, u32> {
+/// every listening socket with the PID that owns it
+pub fn try_get_bindings() -> io::Result<Vec<(u32, Binding)>> {
     let output = Command::new("lsof")
-        .args(&["-iTCP", "-sTCP:LISTEN", "-nP"])
-        .output()
-        .expect("failed to execute lsof");
+        .args(["-iTCP", "-sTCP:LISTEN", "-nP"])
+        .output()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_lsof_output(&stdout)
+    Ok(parse_lsof_bindings(&stdout))
 }
@@ -1,13 +1,16 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use sysinfo::{PidExt, ProcessExt, System, SystemExt};
-use tabled::Tabled;
+use std::io;
+use sysinfo::{PidExt, ProcessExt, System, SystemExt, UserExt};
 
-use crate::port::get_port_pid_map;
+use crate::port::{port_pid_map, try_get_bindings, Binding};
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: i32,
     pub name: String,
+    #[serde(default)]
+    pub user: String,
     pub cmd: Vec<String>,
     pub exe: String,
     pub status: String,
@@ -18,44 +21,57 @@ pub struct ProcessInfo {
     pub start_time: u64,
     pub cwd: String,
     pub ports: Vec<u16>,
+    /// addresses the listening ports are bound to
+    #[serde(default)]
+    pub bindings: Vec<Binding>,
 }
 
-#[derive(Tabled)]
-pub struct DisplayProcessInfo {
-    pub pid: i32,
-    pub name: String,
-    pub ports: String,
-    pub command: String,
+impl ProcessInfo {
+    /// whether the process owns at least one listening port
+    pub fn is_listening(&self) -> bool {
+        !self.ports.is_empty()
+    }
+
+    /// whether any listener is bound to all interfaces
+    pub fn is_exposed(&self) -> bool {
+        self.bindings.iter().any(Binding::is_exposed)
+    }
+
+    /// whether every listener is bound to loopback only
+    pub fn is_loopback_only(&self) -> bool {
+        !self.bindings.is_empty() && self.bindings.iter().all(Binding::is_loopback)
+    }
 }
 
-impl From<&ProcessInfo> for DisplayProcessInfo {
-    fn from(p: &ProcessInfo) -> Self {
-        DisplayProcessInfo {
-            pid: p.pid,
-            name: p.name.clone(),
-            ports: p
-                .ports
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
-            command: p.cmd.join(" "),
-        }
+/// every process with its listening ports; fails when `lsof` cannot run
+pub fn get_all_processes() -> io::Result<Vec<ProcessInfo>> {
+    Ok(get_processes_with_bindings(&try_get_bindings()?))
+}
+
+/// collect processes, attaching ports and bind addresses from fetched bindings
+pub fn get_processes_with_bindings(bindings: &[(u32, Binding)]) -> Vec<ProcessInfo> {
+    let mut processes = get_processes_with_ports(&port_pid_map(bindings));
+    for p in &mut processes {
+        p.bindings = bindings
+            .iter()
+            .filter(|(pid, _)| *pid as i32 == p.pid)
+            .map(|(_, b)| b.clone())
+            .collect();
     }
+    processes
 }
 
-pub fn get_all_processes() -> Vec<ProcessInfo> {
+/// collect processes, attaching ports from an already fetched port map
+pub fn get_processes_with_ports(port_map: &HashMap<u16, u32>) -> Vec<ProcessInfo> {
     let mut sys = System::new_all();
     sys.refresh_all();
 
-    let port_map: HashMap<u16, u32> = get_port_pid_map();
-
     let mut processes: Vec<ProcessInfo> = sys
         .processes()
         .values()
         .map(|p| {
             let pid = p.pid().as_u32() as i32;
-            let ports = port_map
+            let mut ports = port_map
                 .iter()
                 .filter_map(|(port, mapped_pid)| {
                     if *mapped_pid as i32 == pid {
@@ -65,10 +81,18 @@ pub fn get_all_processes() -> Vec<ProcessInfo> {
                     }
                 })
                 .collect::<Vec<u16>>();
+            ports.sort_unstable();
 
             ProcessInfo {
                 pid,
                 name: p.name().to_string(),
+                user: p
+                    .user_id()
+                    .map(|uid| {
+                        sys.get_user_by_id(uid)
+                            .map_or_else(|| uid.to_string(), |u| u.name().to_string())
+                    })
+                    .unwrap_or_default(),
                 cmd: p.cmd().to_vec(),
                 exe: p.exe().display().to_string(),
                 status: format!("{:?}", p.status()),
@@ -79,12 +103,14 @@ pub fn get_all_processes() -> Vec<ProcessInfo> {
                 start_time: p.start_time(),
                 cwd: p.cwd().display().to_string(),
                 ports,
+                bindings: Vec::new(),
             }
         })
         .collect();
 
     // 🔽 ポート数が多い順に並び替え（0ポートのものが下に）
-    processes.sort_by_key(|p| std::cmp::Reverse(p.ports.len()));
+    // tie-break on the PID so rows keep their order across reloads
+    processes.sort_by_key(|p| (std::cmp::Reverse(p.ports.len()), p.pid));
 
     processes
 }
@@ -0,0 +1,87 @@
+use crate::format::format_timestamp;
+use crate::process::ProcessInfo;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{System, SystemExt};
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// the full process and port state of a host at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+    pub host: HostInfo,
+    pub processes: Vec<ProcessInfo>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostInfo {
+    pub hostname: String,
+    pub os: String,
+    pub os_version: String,
+    pub kernel: String,
+}
+
+impl HostInfo {
+    pub fn current() -> Self {
+        let sys = System::new();
+        Self {
+            hostname: sys.host_name().unwrap_or_default(),
+            os: sys.name().unwrap_or_default(),
+            os_version: sys.os_version().unwrap_or_default(),
+            kernel: sys.kernel_version().unwrap_or_default(),
+        }
+    }
+}
+
+impl Snapshot {
+    pub fn capture(processes: Vec<ProcessInfo>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            host: HostInfo::current(),
+            processes,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let snapshot: Self = serde_json::from_str(&text)
+            .with_context(|| format!("invalid snapshot file {}", path.display()))?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            bail!(
+                "{} was written by a newer portsage (snapshot version {})",
+                path.display(),
+                snapshot.version
+            );
+        }
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// a short description such as `web-01 @ 2024-05-01 12:00:00 UTC`
+    pub fn label(&self) -> String {
+        format!(
+            "{} @ {} UTC",
+            self.host.hostname,
+            format_timestamp(self.timestamp)
+        )
+    }
+}
@@ -0,0 +1,234 @@
+use crate::format::{format_bytes, format_timestamp};
+use crate::process::ProcessInfo;
+use anyhow::{bail, Context, Result};
+
+/// a per-row output template such as `{pid}\t{name}\t{ports}\t{cwd}`.
+///
+/// placeholders name a `ProcessInfo` field and may pipe it through helpers:
+/// `{cmd|join: }`, `{cmd|truncate:40}`, `{memory|bytes}`, `{start_time|time}`,
+/// `{name|pad:20}`. `\t`, `\n` and `\\` are unescaped, `{{` and `}}` print braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field(Field, Vec<Helper>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Pid,
+    Name,
+    User,
+    Cmd,
+    Exe,
+    Status,
+    CpuUsage,
+    Memory,
+    VirtualMemory,
+    ParentPid,
+    StartTime,
+    Cwd,
+    Ports,
+    Bindings,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Helper {
+    Join(String),
+    Truncate(usize),
+    Pad(usize),
+    Bytes,
+    Time,
+}
+
+enum Value {
+    Text(String),
+    List(Vec<String>, &'static str),
+}
+
+impl Value {
+    fn into_text(self) -> String {
+        match self {
+            Value::Text(text) => text,
+            Value::List(items, separator) => items.join(separator),
+        }
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => literal.push(unescape(chars.next())),
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('\\') => inner.push(unescape(chars.next())),
+                            Some(c) => inner.push(c),
+                            None => bail!("unclosed `{{` in template"),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_placeholder(&inner)?);
+                }
+                '}' => bail!("unmatched `}}` in template (use `}}}}` for a literal brace)"),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    pub fn render(&self, p: &ProcessInfo) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(text) => text.clone(),
+                Part::Field(field, helpers) => helpers
+                    .iter()
+                    .fold(field.value(p), |value, helper| helper.apply(value))
+                    .into_text(),
+            })
+            .collect()
+    }
+}
+
+fn unescape(c: Option<char>) -> char {
+    match c {
+        Some('t') => '\t',
+        Some('n') => '\n',
+        Some(c) => c,
+        None => '\\',
+    }
+}
+
+fn parse_placeholder(inner: &str) -> Result<Part> {
+    let mut pieces = inner.split('|');
+    let name = pieces.next().unwrap_or_default().trim();
+    let field = Field::parse(name)?;
+    let helpers = pieces.map(Helper::parse).collect::<Result<_>>()?;
+    Ok(Part::Field(field, helpers))
+}
+
+impl Field {
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "pid" => Field::Pid,
+            "name" => Field::Name,
+            "user" => Field::User,
+            "cmd" | "command" => Field::Cmd,
+            "exe" => Field::Exe,
+            "status" => Field::Status,
+            "cpu" | "cpu_usage" => Field::CpuUsage,
+            "mem" | "memory" => Field::Memory,
+            "virtual_memory" => Field::VirtualMemory,
+            "ppid" | "parent_pid" => Field::ParentPid,
+            "start" | "start_time" => Field::StartTime,
+            "cwd" => Field::Cwd,
+            "ports" => Field::Ports,
+            "bindings" => Field::Bindings,
+            _ => bail!("unknown template field `{name}`"),
+        })
+    }
+
+    fn value(self, p: &ProcessInfo) -> Value {
+        match self {
+            Field::Pid => Value::Text(p.pid.to_string()),
+            Field::Name => Value::Text(p.name.clone()),
+            Field::User => Value::Text(p.user.clone()),
+            Field::Cmd => Value::List(p.cmd.clone(), " "),
+            Field::Exe => Value::Text(p.exe.clone()),
+            Field::Status => Value::Text(p.status.clone()),
+            Field::CpuUsage => Value::Text(format!("{:.1}", p.cpu_usage)),
+            Field::Memory => Value::Text(p.memory.to_string()),
+            Field::VirtualMemory => Value::Text(p.virtual_memory.to_string()),
+            Field::ParentPid => {
+                Value::Text(p.parent_pid.map(|x| x.to_string()).unwrap_or_default())
+            }
+            Field::StartTime => Value::Text(p.start_time.to_string()),
+            Field::Cwd => Value::Text(p.cwd.clone()),
+            Field::Ports => Value::List(p.ports.iter().map(|x| x.to_string()).collect(), ","),
+            // only TCP listeners are collected, so the protocol is always tcp
+            Field::Bindings => {
+                Value::List(p.bindings.iter().map(|b| format!("{b}/tcp")).collect(), ",")
+            }
+        }
+    }
+}
+
+impl Helper {
+    fn parse(spec: &str) -> Result<Self> {
+        let (name, arg) = match spec.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (spec.trim(), None),
+        };
+        let number = |arg: Option<&str>| -> Result<usize> {
+            arg.context(format!("`{name}` needs a length, e.g. `{name}:20`"))?
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid length for `{name}`"))
+        };
+
+        Ok(match name {
+            "join" => Helper::Join(arg.unwrap_or(",").to_string()),
+            "truncate" => Helper::Truncate(number(arg)?),
+            "pad" => Helper::Pad(number(arg)?),
+            "bytes" => Helper::Bytes,
+            "time" => Helper::Time,
+            _ => bail!("unknown template helper `{name}`"),
+        })
+    }
+
+    fn apply(&self, value: Value) -> Value {
+        match self {
+            Helper::Join(separator) => match value {
+                Value::List(items, _) => Value::Text(items.join(separator)),
+                text => text,
+            },
+            Helper::Truncate(max) => {
+                let text = value.into_text();
+                if text.chars().count() <= *max {
+                    Value::Text(text)
+                } else if *max == 0 {
+                    // no room for the ellipsis either
+                    Value::Text(String::new())
+                } else {
+                    let kept: String = text.chars().take(max.saturating_sub(1)).collect();
+                    Value::Text(format!("{kept}…"))
+                }
+            }
+            Helper::Pad(width) => Value::Text(format!("{:<width$}", value.into_text())),
+            Helper::Bytes => {
+                let text = value.into_text();
+                Value::Text(text.parse().map(format_bytes).unwrap_or(text))
+            }
+            Helper::Time => {
+                let text = value.into_text();
+                Value::Text(text.parse().map(format_timestamp).unwrap_or(text))
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use clap::ValueEnum;
+use crossterm::style::Stylize;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer};
+use std::env;
+
+/// names of the built-in themes
+pub const BUILTIN_THEMES: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+/// colors used by the TUI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// key help and status lines
+    pub header: Color,
+    /// column titles of the table
+    pub table_header: Color,
+    pub pid: Color,
+    pub name: Color,
+    pub ports: Color,
+    pub command: Color,
+    /// background of the selected row
+    pub selected: Color,
+    /// rows listening on all interfaces
+    pub exposed: Color,
+    /// ports of rows that only listen on loopback
+    pub loopback: Color,
+    /// status messages such as "Copied PID"
+    pub message: Color,
+    /// secondary text in popups
+    pub hint: Color,
+    pub popup_border: Color,
+    pub popup_fg: Color,
+    pub popup_bg: Color,
+    /// border of the kill confirmation
+    pub danger: Color,
+}
+
+/// colors set in the config file, either in `[colors]` on top of the chosen
+/// theme or as a custom theme in `[themes.<name>]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorOverrides {
+    #[serde(deserialize_with = "deserialize_color")]
+    pub header: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub table_header: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub pid: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub name: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub ports: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub command: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub selected: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub exposed: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub loopback: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub message: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub hint: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub popup_border: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub popup_fg: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub popup_bg: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub danger: Option<Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: Color::Cyan,
+            table_header: Color::Yellow,
+            pid: Color::Green,
+            name: Color::Reset,
+            ports: Color::Yellow,
+            command: Color::Blue,
+            selected: Color::DarkGray,
+            exposed: Color::LightRed,
+            loopback: Color::Green,
+            message: Color::Green,
+            hint: Color::DarkGray,
+            popup_border: Color::Yellow,
+            popup_fg: Color::White,
+            popup_bg: Color::Black,
+            danger: Color::Red,
+        }
+    }
+}
+
+impl Theme {
+    /// look up a built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::default()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            header: Color::Blue,
+            table_header: Color::Magenta,
+            pid: Color::Green,
+            name: Color::Reset,
+            ports: Color::Magenta,
+            command: Color::Blue,
+            selected: Color::Gray,
+            exposed: Color::Red,
+            loopback: Color::DarkGray,
+            message: Color::Green,
+            hint: Color::DarkGray,
+            popup_border: Color::Blue,
+            popup_fg: Color::Black,
+            popup_bg: Color::White,
+            danger: Color::Red,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            header: Color::White,
+            table_header: Color::LightYellow,
+            pid: Color::LightGreen,
+            name: Color::White,
+            ports: Color::LightYellow,
+            command: Color::LightCyan,
+            selected: Color::Blue,
+            exposed: Color::LightRed,
+            loopback: Color::LightGreen,
+            message: Color::LightGreen,
+            hint: Color::White,
+            popup_border: Color::White,
+            popup_fg: Color::White,
+            popup_bg: Color::Black,
+            danger: Color::LightRed,
+        }
+    }
+
+    /// no colors at all; the selection is shown reversed
+    pub fn monochrome() -> Self {
+        Self {
+            header: Color::Reset,
+            table_header: Color::Reset,
+            pid: Color::Reset,
+            name: Color::Reset,
+            ports: Color::Reset,
+            command: Color::Reset,
+            selected: Color::Reset,
+            exposed: Color::Reset,
+            loopback: Color::Reset,
+            message: Color::Reset,
+            hint: Color::Reset,
+            popup_border: Color::Reset,
+            popup_fg: Color::Reset,
+            popup_bg: Color::Reset,
+            danger: Color::Reset,
+        }
+    }
+
+    /// replace the colors that are set in `overrides`
+    pub fn with(&self, overrides: &ColorOverrides) -> Self {
+        Self {
+            header: overrides.header.unwrap_or(self.header),
+            table_header: overrides.table_header.unwrap_or(self.table_header),
+            pid: overrides.pid.unwrap_or(self.pid),
+            name: overrides.name.unwrap_or(self.name),
+            ports: overrides.ports.unwrap_or(self.ports),
+            command: overrides.command.unwrap_or(self.command),
+            selected: overrides.selected.unwrap_or(self.selected),
+            exposed: overrides.exposed.unwrap_or(self.exposed),
+            loopback: overrides.loopback.unwrap_or(self.loopback),
+            message: overrides.message.unwrap_or(self.message),
+            hint: overrides.hint.unwrap_or(self.hint),
+            popup_border: overrides.popup_border.unwrap_or(self.popup_border),
+            popup_fg: overrides.popup_fg.unwrap_or(self.popup_fg),
+            popup_bg: overrides.popup_bg.unwrap_or(self.popup_bg),
+            danger: overrides.danger.unwrap_or(self.danger),
+        }
+    }
+
+    /// style of the selected row, reversed when the theme has no selection color
+    pub fn selected_style(&self) -> Style {
+        match self.selected {
+            Color::Reset => Style::default().add_modifier(Modifier::REVERSED),
+            color => Style::default().bg(color),
+        }
+    }
+
+    /// every color slot with its config key, in declaration order
+    pub fn entries(&self) -> [(&'static str, Color); 15] {
+        [
+            ("header", self.header),
+            ("table_header", self.table_header),
+            ("pid", self.pid),
+            ("name", self.name),
+            ("ports", self.ports),
+            ("command", self.command),
+            ("selected", self.selected),
+            ("exposed", self.exposed),
+            ("loopback", self.loopback),
+            ("message", self.message),
+            ("hint", self.hint),
+            ("popup_border", self.popup_border),
+            ("popup_fg", self.popup_fg),
+            ("popup_bg", self.popup_bg),
+            ("danger", self.danger),
+        ]
+    }
+}
+
+/// color names as written in the config file, e.g. `darkgray` or `#ff8800`
+pub fn format_color(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
+        Color::Indexed(i) => i.to_string(),
+        named => named.to_string().to_ascii_lowercase(),
+    }
+}
+
+fn deserialize_color<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Color>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    text.parse()
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("unknown color `{text}`")))
+}
+
+/// when to use colors, for `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Use colors unless NO_COLOR is set or output is not a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// whether to use colors on an output that is or is not a terminal
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && !no_color(),
+        }
+    }
+}
+
+/// the `NO_COLOR` convention: set and not empty
+fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+/// wrap `text` in ANSI color codes for CLI output when colors are enabled
+pub fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if enabled && color != Color::Reset {
+        text.with(crossterm::style::Color::from(color)).to_string()
+    } else {
+        text.to_string()
+    }
+}
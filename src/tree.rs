@@ -0,0 +1,95 @@
+use crate::process::ProcessInfo;
+use std::collections::{HashMap, HashSet};
+
+/// render processes as a parent/child tree.
+/// processes matching `keep` are shown together with all of their ancestors.
+pub fn render_tree<F>(processes: &[ProcessInfo], keep: F) -> String
+where
+    F: Fn(&ProcessInfo) -> bool,
+{
+    let by_pid: HashMap<i32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+
+    let mut visible = HashSet::new();
+    for proc in processes.iter().filter(|p| keep(p)) {
+        let mut current = Some(proc);
+        while let Some(p) = current {
+            if !visible.insert(p.pid) {
+                break;
+            }
+            current = p.parent_pid.and_then(|ppid| by_pid.get(&ppid).copied());
+        }
+    }
+
+    let mut children: HashMap<i32, Vec<&ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+    for proc in processes.iter().filter(|p| visible.contains(&p.pid)) {
+        match proc.parent_pid.filter(|ppid| visible.contains(ppid)) {
+            Some(ppid) if ppid != proc.pid => children.entry(ppid).or_default().push(proc),
+            _ => roots.push(proc),
+        }
+    }
+    roots.sort_by_key(|p| p.pid);
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|p| p.pid);
+    }
+
+    let mut out = String::new();
+    let mut printed = HashSet::new();
+    for root in roots {
+        out.push_str(&node_label(root));
+        out.push('\n');
+        printed.insert(root.pid);
+        write_children(&mut out, root.pid, &children, "", &mut printed);
+    }
+    out
+}
+
+fn write_children(
+    out: &mut String,
+    pid: i32,
+    children: &HashMap<i32, Vec<&ProcessInfo>>,
+    prefix: &str,
+    printed: &mut HashSet<i32>,
+) {
+    let Some(siblings) = children.get(&pid) else {
+        return;
+    };
+
+    for (i, child) in siblings.iter().enumerate() {
+        // guard against parent cycles in racy process snapshots
+        if !printed.insert(child.pid) {
+            continue;
+        }
+        let last = i + 1 == siblings.len();
+        let (branch, indent) = if last {
+            ("└─ ", "   ")
+        } else {
+            ("├─ ", "│  ")
+        };
+        out.push_str(prefix);
+        out.push_str(branch);
+        out.push_str(&node_label(child));
+        out.push('\n');
+        write_children(
+            out,
+            child.pid,
+            children,
+            &format!("{prefix}{indent}"),
+            printed,
+        );
+    }
+}
+
+fn node_label(p: &ProcessInfo) -> String {
+    if p.ports.is_empty() {
+        format!("{} {}", p.pid, p.name)
+    } else {
+        let ports = p
+            .ports
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{} {} [{ports}]", p.pid, p.name)
+    }
+}
@@ -0,0 +1,628 @@
+use super::copy::{copy_text, CopyTarget};
+use super::detail::tab_lines;
+use super::filter::apply_filter;
+use super::state::{
+    ClipboardMessage, ColumnChooser, DetailTab, ListPosition, Mode, PaneLayout, SortOrder,
+};
+use super::view::{
+    column_chooser_item_at, copy_menu_item_at, detail_hit, detail_rows, fitted_layout,
+    help_max_scroll, kill_button_at, table_hit, table_rows, DetailHit, TableHit,
+};
+use super::TuiOptions;
+use crate::bindings::{HelpSection, KeyBindings};
+use crate::columns::Column;
+use crate::config::Config;
+use crate::history::FilterHistory;
+use crate::process::ProcessInfo;
+use crate::procfs::ProcDetails;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// rows moved per mouse wheel step
+const WHEEL_ROWS: isize = 3;
+/// two clicks on the same row within this time open the detail view
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// what the event loop has to do after the app handled an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    /// stop the process until the shell resumes it
+    Suspend,
+    /// load the processes again
+    Reload,
+    /// send SIGKILL to this PID
+    Kill(i32),
+    /// read the detail tabs of this PID from /proc
+    LoadDetails(i32),
+    /// put `text` on the clipboard; `label` names it in the status line
+    Copy {
+        text: String,
+        label: String,
+    },
+    /// write the chosen columns to the config file
+    SaveColumns(Vec<Column>),
+}
+
+/// the state of the TUI, changed only through events
+pub struct App {
+    pub(super) processes: Vec<ProcessInfo>,
+    /// `processes` after the filter and the sort order
+    pub(super) filtered: Vec<ProcessInfo>,
+    pub(super) position: ListPosition,
+    /// PIDs marked for copying several processes at once
+    pub(super) marked: BTreeSet<i32>,
+    pub(super) mode: Mode,
+    pub(super) filter_input: String,
+    pub(super) listening_only: bool,
+    pub(super) sort: Option<SortOrder>,
+    /// the layout picked with the layout key; narrow terminals may show less
+    pub(super) pane: PaneLayout,
+    pub(super) message: ClipboardMessage,
+    pub(super) columns: Vec<Column>,
+    pub(super) column_chooser: ColumnChooser,
+    /// the highlighted entry of the copy menu
+    pub(super) copy_cursor: usize,
+    pub(super) help: Vec<HelpSection>,
+    pub(super) help_scroll: usize,
+    pub(super) detail_tab: DetailTab,
+    pub(super) detail_scroll: usize,
+    /// the /proc details of the PID in the detail view
+    details: Option<(i32, ProcDetails)>,
+    pub(super) options: TuiOptions,
+    history: FilterHistory,
+    /// the terminal size of the last frame, for paging and mouse clicks
+    size: Rect,
+    last_refresh: Instant,
+    /// first half of `g g`
+    g_pressed: bool,
+    /// row and time of the last click, for double clicks
+    last_click: Option<(usize, Instant)>,
+}
+
+impl App {
+    pub fn new(
+        processes: Vec<ProcessInfo>,
+        mut options: TuiOptions,
+        history: FilterHistory,
+    ) -> Self {
+        let filter_input = options.filter.take().unwrap_or_default();
+        let filtered = apply_filter(&processes, &filter_input, options.listening_only, None);
+        let help = help_sections(&options.config.keys, &options.config, options.mouse);
+        Self {
+            processes,
+            filtered,
+            position: ListPosition::default(),
+            marked: BTreeSet::new(),
+            mode: Mode::Normal,
+            filter_input,
+            listening_only: options.listening_only,
+            sort: None,
+            pane: PaneLayout::default(),
+            message: ClipboardMessage::default(),
+            columns: std::mem::take(&mut options.columns),
+            column_chooser: ColumnChooser::default(),
+            copy_cursor: 0,
+            help,
+            help_scroll: 0,
+            detail_tab: DetailTab::default(),
+            detail_scroll: 0,
+            details: None,
+            options,
+            history,
+            size: Rect::default(),
+            last_refresh: Instant::now(),
+            g_pressed: false,
+            last_click: None,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the processes shown, in display order
+    pub fn shown(&self) -> &[ProcessInfo] {
+        &self.filtered
+    }
+
+    pub fn selected(&self) -> Option<&ProcessInfo> {
+        self.filtered.get(self.position.selected)
+    }
+
+    /// snapshots are never reloaded
+    pub fn is_live(&self) -> bool {
+        self.options.snapshot.is_none()
+    }
+
+    /// remember the terminal size and keep the selection on screen
+    pub fn resize(&mut self, size: Rect) {
+        self.size = size;
+        self.position
+            .scroll_into_view(table_rows(size, self.pane), self.filtered.len());
+    }
+
+    /// whether the periodic reload is due; never while a kill is being confirmed
+    pub fn refresh_due(&self) -> bool {
+        let refresh = self.options.refresh;
+        self.is_live()
+            && !refresh.is_zero()
+            && self.last_refresh.elapsed() >= refresh
+            && self.mode != Mode::ConfirmKill
+    }
+
+    /// the processes the copy menu copies: the marked ones, or else the selected one
+    pub fn copy_targets(&self) -> Vec<&ProcessInfo> {
+        if self.marked.is_empty() {
+            return self.selected().into_iter().collect();
+        }
+        // in list order, then those the filter hides
+        let hidden = self
+            .processes
+            .iter()
+            .filter(|p| !self.filtered.iter().any(|shown| shown.pid == p.pid));
+        self.filtered
+            .iter()
+            .chain(hidden)
+            .filter(|p| self.marked.contains(&p.pid))
+            .collect()
+    }
+
+    /// replace the processes, keeping the selected PID selected when it still exists
+    pub fn set_processes(&mut self, processes: Vec<ProcessInfo>) {
+        self.processes = processes;
+        let processes = &self.processes;
+        self.marked
+            .retain(|pid| processes.iter().any(|p| p.pid == *pid));
+        self.last_refresh = Instant::now();
+        self.refilter();
+    }
+
+    /// keep the current list when reloading failed, and wait for the next
+    /// refresh before trying again
+    pub fn reload_failed(&mut self, error: &str) {
+        self.last_refresh = Instant::now();
+        self.notify(format!("✖ Failed to reload processes: {error}"));
+    }
+
+    pub fn set_details(&mut self, pid: i32, details: ProcDetails) {
+        self.details = Some((pid, details));
+    }
+
+    /// the lines of the current detail tab for the selected process
+    pub fn detail_lines(&self) -> Vec<String> {
+        let Some(proc) = self.selected() else {
+            return Vec::new();
+        };
+        let details = self
+            .details
+            .as_ref()
+            .filter(|(pid, _)| *pid == proc.pid)
+            .map(|(_, d)| d);
+        tab_lines(self.detail_tab, proc, details, self.is_live())
+    }
+
+    /// show `text` in the status line
+    pub fn notify(&mut self, text: impl Into<String>) {
+        self.message.message = Some((text.into(), Instant::now()));
+    }
+
+    fn refilter(&mut self) {
+        let selected_pid = self.selected().map(|p| p.pid);
+        self.filtered = apply_filter(
+            &self.processes,
+            &self.filter_input,
+            self.listening_only,
+            self.sort,
+        );
+        let index = selected_pid
+            .and_then(|pid| self.filtered.iter().position(|p| p.pid == pid))
+            .unwrap_or(self.position.selected);
+        self.position.select(index, self.filtered.len());
+    }
+
+    /// apply a new filter and start again at the top
+    fn restart_filter(&mut self) {
+        self.filtered = apply_filter(
+            &self.processes,
+            &self.filter_input,
+            self.listening_only,
+            self.sort,
+        );
+        self.position = ListPosition::default();
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        // what the terminal would do with these outside raw mode
+        if key.modifiers == KeyModifiers::CONTROL {
+            match key.code {
+                KeyCode::Char('c') => return Action::Quit,
+                KeyCode::Char('z') => return Action::Suspend,
+                _ => {}
+            }
+        }
+
+        match self.mode {
+            Mode::Normal => self.normal_key(key),
+            Mode::FilterInput => {
+                self.filter_key(key);
+                Action::None
+            }
+            Mode::Detail => self.detail_key(key),
+            Mode::CopyMenu => self.copy_menu_key(key),
+            Mode::Help => {
+                let bindings = &self.options.config.keys;
+                if bindings.is_quit(&key) || bindings.is_help(&key) {
+                    self.mode = Mode::Normal;
+                } else if bindings.is_down(&key) {
+                    self.scroll_help(1);
+                } else if bindings.is_up(&key) {
+                    self.scroll_help(-1);
+                }
+                Action::None
+            }
+            Mode::Columns => self.columns_key(key),
+            Mode::ConfirmKill => self.answer_kill(key.code == KeyCode::Char('y')),
+        }
+    }
+
+    fn normal_key(&mut self, key: KeyEvent) -> Action {
+        let len = self.filtered.len();
+        let page = table_rows(self.size, self.pane).max(1) as isize;
+        let half_page = (page / 2).max(1);
+        let g_pending = std::mem::take(&mut self.g_pressed);
+        let bindings: &KeyBindings = &self.options.config.keys;
+
+        match key {
+            _ if bindings.is_quit(&key) => return Action::Quit,
+            _ if bindings.is_down(&key) => self.position.move_by(1, len),
+            _ if bindings.is_up(&key) => self.position.move_by(-1, len),
+            _ if bindings.is_page_down(&key) => self.position.move_by(page, len),
+            _ if bindings.is_page_up(&key) => self.position.move_by(-page, len),
+            _ if bindings.is_half_page_down(&key) => self.position.move_by(half_page, len),
+            _ if bindings.is_half_page_up(&key) => self.position.move_by(-half_page, len),
+            _ if bindings.is_top(&key) => self.position.select(0, len),
+            _ if bindings.is_bottom(&key) => self.position.select(usize::MAX, len),
+            _ if bindings.is_filter(&key) => {
+                self.mode = Mode::FilterInput;
+                self.filter_input.clear();
+                self.history.reset();
+            }
+            _ if bindings.is_detail(&key) => return self.open_detail(),
+            _ if bindings.is_copy(&key) && !self.copy_targets().is_empty() => {
+                self.copy_cursor = 0;
+                self.mode = Mode::CopyMenu;
+            }
+            _ if bindings.is_mark(&key) => {
+                if let Some(pid) = self.selected().map(|p| p.pid) {
+                    if !self.marked.remove(&pid) {
+                        self.marked.insert(pid);
+                    }
+                    self.position.move_by(1, len);
+                }
+            }
+            _ if bindings.is_kill(&key) => {
+                if self.is_live() {
+                    self.mode = Mode::ConfirmKill;
+                } else {
+                    self.notify("✖ Processes in a snapshot cannot be killed");
+                }
+            }
+            _ if bindings.is_help(&key) => {
+                self.help_scroll = 0;
+                self.mode = Mode::Help;
+            }
+            _ if bindings.is_columns(&key) => {
+                self.column_chooser = ColumnChooser::new(&self.columns);
+                self.mode = Mode::Columns;
+            }
+            _ if bindings.is_layout(&key) => {
+                self.pane = self.pane.next();
+                let shown = fitted_layout(self.size, self.pane);
+                self.notify(if shown == self.pane {
+                    format!("Layout: {}", self.pane)
+                } else {
+                    format!(
+                        "Layout: {} (showing {shown}, the terminal is too small)",
+                        self.pane
+                    )
+                });
+            }
+            _ if bindings.is_refresh(&key) && self.is_live() => return Action::Reload,
+            _ if bindings.is_listening(&key) => {
+                self.listening_only = !self.listening_only;
+                self.restart_filter();
+            }
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                if g_pending {
+                    self.position.select(0, len);
+                } else {
+                    self.g_pressed = true;
+                }
+            }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                ..
+            } => {
+                if let Some((name, preset)) = self.options.config.preset_for_key(c) {
+                    let text = format!("Preset: {name}");
+                    self.filter_input = preset.filter.clone();
+                    self.restart_filter();
+                    self.notify(text);
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn open_detail(&mut self) -> Action {
+        self.mode = Mode::Detail;
+        self.detail_scroll = 0;
+        match self.selected() {
+            Some(proc) if self.is_live() => Action::LoadDetails(proc.pid),
+            _ => Action::None,
+        }
+    }
+
+    fn detail_key(&mut self, key: KeyEvent) -> Action {
+        let page = detail_rows(self.size).max(1) as isize;
+        let bindings = &self.options.config.keys;
+        match key.code {
+            _ if bindings.is_quit(&key) || bindings.is_detail(&key) => self.mode = Mode::Normal,
+            _ if bindings.is_copy(&key) => {
+                return Action::Copy {
+                    text: self.detail_lines().join("\n"),
+                    label: format!("{} tab", self.detail_tab.title()),
+                };
+            }
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => self.switch_tab(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.switch_tab(1),
+            KeyCode::Char(c @ '1'..='6') => {
+                self.detail_tab = DetailTab::ALL[c as usize - '1' as usize];
+                self.detail_scroll = 0;
+            }
+            _ if bindings.is_down(&key) => self.scroll_detail(1),
+            _ if bindings.is_up(&key) => self.scroll_detail(-1),
+            _ if bindings.is_page_down(&key) => self.scroll_detail(page),
+            _ if bindings.is_page_up(&key) => self.scroll_detail(-page),
+            _ if bindings.is_top(&key) => self.detail_scroll = 0,
+            _ if bindings.is_bottom(&key) => self.scroll_detail(isize::MAX),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn copy_menu_key(&mut self, key: KeyEvent) -> Action {
+        let bindings = &self.options.config.keys;
+        let last = CopyTarget::ALL.len() - 1;
+        match key.code {
+            _ if bindings.is_quit(&key) => self.mode = Mode::Normal,
+            KeyCode::Char(c @ '1'..='8') => {
+                return self.copy(CopyTarget::ALL[c as usize - '1' as usize]);
+            }
+            KeyCode::Enter => return self.copy(CopyTarget::ALL[self.copy_cursor]),
+            _ if bindings.is_down(&key) => self.copy_cursor = (self.copy_cursor + 1).min(last),
+            _ if bindings.is_up(&key) => self.copy_cursor = self.copy_cursor.saturating_sub(1),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn copy(&mut self, target: CopyTarget) -> Action {
+        self.mode = Mode::Normal;
+        let processes = self.copy_targets();
+        let label = match processes.len() {
+            1 => target.label().to_string(),
+            n => format!("{} of {n} processes", target.label()),
+        };
+        match copy_text(target, &processes) {
+            Some(text) => Action::Copy { text, label },
+            None => {
+                self.notify(format!("✖ {label}: nothing to copy"));
+                Action::None
+            }
+        }
+    }
+
+    fn switch_tab(&mut self, delta: isize) {
+        self.detail_tab = self.detail_tab.cycle(delta);
+        self.detail_scroll = 0;
+    }
+
+    fn scroll_detail(&mut self, delta: isize) {
+        let max = self
+            .detail_lines()
+            .len()
+            .saturating_sub(detail_rows(self.size));
+        self.detail_scroll = self.detail_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    fn filter_key(&mut self, key: KeyEvent) {
+        let edited = match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                false
+            }
+            KeyCode::Enter => {
+                self.history.push(&self.filter_input);
+                let _ = self.history.save();
+                self.mode = Mode::Normal;
+                false
+            }
+            KeyCode::Up => match self.history.older(&self.filter_input) {
+                Some(entry) => {
+                    self.filter_input = entry.to_string();
+                    true
+                }
+                None => false,
+            },
+            KeyCode::Down => match self.history.newer() {
+                Some(entry) => {
+                    self.filter_input = entry.to_string();
+                    true
+                }
+                None => false,
+            },
+            KeyCode::Char(c) => {
+                self.filter_input.push(c);
+                self.history.reset();
+                true
+            }
+            KeyCode::Backspace => {
+                self.filter_input.pop();
+                self.history.reset();
+                true
+            }
+            _ => false,
+        };
+        if edited {
+            self.restart_filter();
+        }
+    }
+
+    fn columns_key(&mut self, key: KeyEvent) -> Action {
+        let bindings = &self.options.config.keys;
+        match key.code {
+            _ if bindings.is_quit(&key) || bindings.is_columns(&key) => self.mode = Mode::Normal,
+            KeyCode::Char(' ') => self.column_chooser.toggle(),
+            KeyCode::Enter => {
+                let selected = self.column_chooser.selected();
+                if selected.is_empty() {
+                    self.notify("✖ Select at least one column");
+                } else {
+                    self.columns = selected.clone();
+                    self.mode = Mode::Normal;
+                    return Action::SaveColumns(selected);
+                }
+            }
+            _ if bindings.is_up(&key) => self.column_chooser.up(),
+            _ if bindings.is_down(&key) => self.column_chooser.down(),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn answer_kill(&mut self, yes: bool) -> Action {
+        self.mode = Mode::Normal;
+        match self.selected() {
+            Some(proc) if yes => Action::Kill(proc.pid),
+            _ => Action::None,
+        }
+    }
+
+    fn scroll_help(&mut self, delta: isize) {
+        let help_lines = self.help.iter().map(|s| s.entries.len() + 2).sum::<usize>() - 1;
+        let max = help_max_scroll(help_lines, self.size.height as usize);
+        self.help_scroll = self.help_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Action {
+        let len = self.filtered.len();
+        let click = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        let wheel = match mouse.kind {
+            MouseEventKind::ScrollDown => WHEEL_ROWS,
+            MouseEventKind::ScrollUp => -WHEEL_ROWS,
+            _ => 0,
+        };
+
+        match self.mode {
+            Mode::Normal if click => match table_hit(self.size, self, mouse.column, mouse.row) {
+                Some(TableHit::Row(index)) => {
+                    self.position.select(index, len);
+                    let double = self
+                        .last_click
+                        .take()
+                        .is_some_and(|(row, at)| row == index && at.elapsed() < DOUBLE_CLICK);
+                    if double {
+                        return self.open_detail();
+                    }
+                    self.last_click = Some((index, Instant::now()));
+                }
+                Some(TableHit::Title(column)) => {
+                    self.sort = Some(SortOrder::toggle(self.sort, column));
+                    self.refilter();
+                }
+                None => {}
+            },
+            Mode::Normal => self.position.move_by(wheel, len),
+            Mode::Detail if click => match detail_hit(self.size, mouse.column, mouse.row) {
+                Some(DetailHit::Tab(tab)) => {
+                    self.detail_tab = tab;
+                    self.detail_scroll = 0;
+                }
+                Some(DetailHit::Inside) => {}
+                None => self.mode = Mode::Normal,
+            },
+            Mode::Detail => self.scroll_detail(wheel),
+            Mode::CopyMenu if click => {
+                match copy_menu_item_at(self.size, mouse.column, mouse.row) {
+                    Some(index) => return self.copy(CopyTarget::ALL[index]),
+                    None => self.mode = Mode::Normal,
+                }
+            }
+            Mode::Help if click => self.mode = Mode::Normal,
+            Mode::Help => self.scroll_help(wheel),
+            Mode::Columns if click => {
+                let chooser = &self.column_chooser;
+                if let Some(index) =
+                    column_chooser_item_at(self.size, chooser, mouse.column, mouse.row)
+                {
+                    self.column_chooser.cursor = index;
+                    self.column_chooser.toggle();
+                }
+            }
+            Mode::ConfirmKill if click => {
+                let yes = kill_button_at(self.size, mouse.column, mouse.row).unwrap_or(false);
+                return self.answer_kill(yes);
+            }
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// the help overlay: the bindings in effect plus the configured preset keys
+fn help_sections(bindings: &KeyBindings, config: &Config, mouse: bool) -> Vec<HelpSection> {
+    let mut sections = bindings.help_sections();
+    let presets: Vec<(String, String)> = config
+        .presets
+        .iter()
+        .filter_map(|(name, p)| p.key.map(|k| (k.to_string(), format!("Preset: {name}"))))
+        .collect();
+    if !presets.is_empty() {
+        sections.insert(
+            1,
+            HelpSection {
+                mode: "Presets",
+                entries: presets,
+            },
+        );
+    }
+    if mouse {
+        let entries = [
+            (
+                "click",
+                "Select a row, sort by a column title, press a button",
+            ),
+            ("double-click", "Show details"),
+            ("wheel", "Scroll"),
+        ];
+        sections.push(HelpSection {
+            mode: "Mouse",
+            entries: entries
+                .into_iter()
+                .map(|(k, a)| (k.to_string(), a.to_string()))
+                .collect(),
+        });
+    }
+    sections
+}
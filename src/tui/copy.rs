@@ -0,0 +1,84 @@
+use crate::format::join_ports;
+use crate::process::ProcessInfo;
+
+/// what the copy menu can put on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyTarget {
+    Pid,
+    Ports,
+    Url,
+    Cmdline,
+    Cwd,
+    Exe,
+    Summary,
+    Json,
+}
+
+impl CopyTarget {
+    /// in menu order; the menu numbers them from 1
+    pub const ALL: [CopyTarget; 8] = [
+        CopyTarget::Pid,
+        CopyTarget::Ports,
+        CopyTarget::Url,
+        CopyTarget::Cmdline,
+        CopyTarget::Cwd,
+        CopyTarget::Exe,
+        CopyTarget::Summary,
+        CopyTarget::Json,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CopyTarget::Pid => "PID",
+            CopyTarget::Ports => "Ports",
+            CopyTarget::Url => "URL",
+            CopyTarget::Cmdline => "Command line",
+            CopyTarget::Cwd => "Working directory",
+            CopyTarget::Exe => "Executable",
+            CopyTarget::Summary => "Summary",
+            CopyTarget::Json => "JSON",
+        }
+    }
+}
+
+/// the text to copy for `processes`, one line per process and one per port for
+/// ports and URLs; `None` when none of them has the value
+pub fn copy_text(target: CopyTarget, processes: &[&ProcessInfo]) -> Option<String> {
+    let lines: Vec<String> = processes
+        .iter()
+        .flat_map(|p| match target {
+            CopyTarget::Pid => vec![p.pid.to_string()],
+            CopyTarget::Ports => p.ports.iter().map(u16::to_string).collect(),
+            CopyTarget::Url => p
+                .ports
+                .iter()
+                .map(|port| format!("http://localhost:{port}"))
+                .collect(),
+            CopyTarget::Cmdline => vec![p.cmd.join(" ")],
+            CopyTarget::Cwd => vec![p.cwd.clone()],
+            CopyTarget::Exe => vec![p.exe.clone()],
+            CopyTarget::Summary => vec![summary(p)],
+            // one object per line, like `watch --json`
+            CopyTarget::Json => serde_json::to_string(p).into_iter().collect(),
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// e.g. `node (pid 200) on 3000, 3001: /usr/bin/node server.js`
+fn summary(p: &ProcessInfo) -> String {
+    let mut text = format!("{} (pid {})", p.name, p.pid);
+    if !p.ports.is_empty() {
+        text.push_str(&format!(" on {}", join_ports(&p.ports)));
+    }
+    let command = if p.cmd.is_empty() {
+        p.exe.clone()
+    } else {
+        p.cmd.join(" ")
+    };
+    if !command.is_empty() {
+        text.push_str(&format!(": {command}"));
+    }
+    text
+}
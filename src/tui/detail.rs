@@ -1,36 +1,145 @@
+use super::state::DetailTab;
+use crate::format::format_bytes;
 use crate::process::ProcessInfo;
+use crate::procfs::{mask_value, ProcDetails};
+use crate::theme::Theme;
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Paragraph},
 };
 
-pub fn draw_process_detail(proc: &ProcessInfo) -> Paragraph {
-    let detail = format!(
-        "PID: {}\n\
-         Name: {}\n\
-         Status: {}\n\
-         CPU Usage: {:.2}%\n\
-         Memory: {} KB\n\
-         Virtual Memory: {} KB\n\
-         Parent PID: {}\n\
-         Start Time: {}\n\
-         Exe: {}\n\
-         CWD: {}\n\
-         Cmd: {}",
-        proc.pid,
-        proc.name,
-        proc.status,
-        proc.cpu_usage,
-        proc.memory,
-        proc.virtual_memory,
-        proc.parent_pid.map_or("N/A".to_string(), |p| p.to_string()),
-        proc.start_time,
-        proc.exe,
-        proc.cwd,
-        proc.cmd.join(" ")
-    );
+pub fn draw_process_detail<'a>(proc: &'a ProcessInfo, theme: &Theme) -> Paragraph<'a> {
+    Paragraph::new(overview_lines(proc).join("\n"))
+        .block(
+            Block::default()
+                .title("Process Detail")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.popup_border)),
+        )
+        .style(Style::default().fg(theme.popup_fg).bg(theme.popup_bg))
+}
+
+fn overview_lines(proc: &ProcessInfo) -> Vec<String> {
+    vec![
+        format!("PID: {}", proc.pid),
+        format!("Name: {}", proc.name),
+        format!("Status: {}", proc.status),
+        format!("CPU Usage: {:.2}%", proc.cpu_usage),
+        format!("Memory: {}", format_bytes(proc.memory)),
+        format!("Virtual Memory: {}", format_bytes(proc.virtual_memory)),
+        format!(
+            "Parent PID: {}",
+            proc.parent_pid.map_or("N/A".into(), |p| p.to_string())
+        ),
+        format!("Start Time: {}", proc.start_time),
+        format!("Exe: {}", proc.exe),
+        format!("CWD: {}", proc.cwd),
+        format!(
+            "Ports: {}",
+            proc.ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        format!(
+            "Listen: {}",
+            proc.bindings
+                .iter()
+                .map(|b| if b.is_exposed() {
+                    format!("{b} (exposed)")
+                } else {
+                    b.to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        format!("Cmd: {}", proc.cmd.join(" ")),
+    ]
+}
+
+/// the text of a detail tab, as shown and as copied; `details` is `None` while
+/// they are loading, and for snapshots
+pub fn tab_lines(
+    tab: DetailTab,
+    proc: &ProcessInfo,
+    details: Option<&ProcDetails>,
+    live: bool,
+) -> Vec<String> {
+    let failed = |file: &str, e: &str| vec![format!("Cannot read /proc/{}/{file}: {e}", proc.pid)];
 
-    Paragraph::new(detail)
-        .style(Style::default().fg(Color::White))
-        .block(Block::default().title("Details").borders(Borders::ALL))
+    match (tab, details) {
+        (DetailTab::Overview, _) => overview_lines(proc),
+        (_, None) if live => vec!["Loading...".to_string()],
+        (_, None) => vec!["Not available for processes in a snapshot".to_string()],
+        (DetailTab::Environment, Some(details)) => match details.environ {
+            Ok(ref vars) if vars.is_empty() => vec!["No environment variables".into()],
+            Ok(ref vars) => {
+                let mut vars: Vec<String> = vars
+                    .iter()
+                    .map(|(k, v)| format!("{k}={}", mask_value(k, v)))
+                    .collect();
+                vars.sort();
+                vars
+            }
+            Err(ref e) => failed("environ", e),
+        },
+        (DetailTab::Files, Some(details)) => match details.open_files {
+            Ok(ref files) => std::iter::once(format!("{} open file descriptors", files.len()))
+                .chain(files.iter().map(|f| format!("{:>5}  {}", f.fd, f.target)))
+                .collect(),
+            Err(ref e) => failed("fd", e),
+        },
+        (DetailTab::Limits, Some(details)) => match details.limits {
+            Ok(ref limits) => {
+                let width = limits.iter().map(|l| l.name.len()).max().unwrap_or(0);
+                let row = |name: &str, soft: &str, hard: &str, units: &str| {
+                    format!("{name:<width$}  {soft:<12}  {hard:<12}  {units}")
+                        .trim_end()
+                        .to_string()
+                };
+                std::iter::once(row("Limit", "Soft", "Hard", "Units"))
+                    .chain(
+                        limits
+                            .iter()
+                            .map(|l| row(&l.name, &l.soft, &l.hard, &l.units)),
+                    )
+                    .collect()
+            }
+            Err(ref e) => failed("limits", e),
+        },
+        (DetailTab::Sockets, Some(details)) => match (&details.sockets, &details.open_files) {
+            (Ok(sockets), Ok(files)) => {
+                let mut lines: Vec<String> = sockets.iter().map(|s| s.to_string()).collect();
+                if lines.is_empty() {
+                    lines.push("No TCP or UDP sockets".into());
+                }
+                let all = files.iter().filter(|f| f.socket_inode().is_some()).count();
+                let other = all.saturating_sub(sockets.len());
+                if other > 0 {
+                    lines.push(format!("{other} other sockets (unix, netlink, ...)"));
+                }
+                lines
+            }
+            (_, Err(e)) => failed("fd", e),
+            (Err(e), _) => failed("net", e),
+        },
+        (DetailTab::Maps, Some(details)) => {
+            match details.maps {
+                Ok(ref maps) => {
+                    let total: u64 = maps.iter().map(|m| m.size).sum();
+                    let regions: usize = maps.iter().map(|m| m.count).sum();
+                    std::iter::once(format!(
+                        "{} mapped in {regions} regions",
+                        format_bytes(total)
+                    ))
+                    .chain(maps.iter().map(|m| {
+                        format!("{:>10}  {:>4}x  {}", format_bytes(m.size), m.count, m.name)
+                    }))
+                    .collect()
+                }
+                Err(ref e) => failed("maps", e),
+            }
+        }
+    }
 }
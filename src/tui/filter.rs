@@ -1,14 +1,28 @@
+use super::state::SortOrder;
+use crate::filter::filter_processes;
 use crate::process::ProcessInfo;
 
-pub fn apply_filter(processes: &[ProcessInfo], keyword: &str) -> Vec<ProcessInfo> {
-    let keyword = keyword.to_lowercase();
-    processes
-        .iter()
-        .filter(|p| {
-            p.pid.to_string().contains(&keyword)
-                || p.name.to_lowercase().contains(&keyword)
-                || p.cmd.iter().any(|c| c.to_lowercase().contains(&keyword))
-        })
+pub fn apply_filter(
+    processes: &[ProcessInfo],
+    keyword: &str,
+    listening_only: bool,
+    sort: Option<SortOrder>,
+) -> Vec<ProcessInfo> {
+    let mut filtered: Vec<ProcessInfo> = filter_processes(processes, keyword)
+        .into_iter()
+        .filter(|p| !listening_only || p.is_listening())
         .cloned()
-        .collect()
+        .collect();
+
+    if let Some(order) = sort {
+        filtered.sort_by(|a, b| {
+            let ordering = order.column.compare(a, b);
+            if order.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+    filtered
 }
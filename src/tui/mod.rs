@@ -1,148 +1,134 @@
+mod app;
 mod clipboard;
+mod copy;
 mod detail;
 mod filter;
-mod render;
 mod state;
+mod terminal;
 mod view;
 
-use crate::{bindings::KeyBindings, process::ProcessInfo};
-use anyhow::Result;
-use clipboard::copy_pid_to_clipboard;
-use crossterm::{
-    event::{self, Event, KeyEvent},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+use crate::{
+    columns::Column,
+    config::{save_columns, Config},
+    history::FilterHistory,
+    process::{get_all_processes, ProcessInfo},
+    procfs::read_details,
+    theme::Theme,
 };
-use filter::apply_filter;
+use anyhow::Result;
+pub use app::{Action, App};
+use clipboard::copy_to_clipboard;
+pub use copy::{copy_text, CopyTarget};
+use crossterm::event::{self, Event};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use state::{ClipboardMessage, Mode};
+use signal_hook::consts::{SIGINT, SIGTERM, SIGTSTP};
+use signal_hook::iterator::Signals;
+pub use state::{DetailTab, ListPosition, Mode, PaneLayout, SortOrder};
 use std::io;
-use view::draw_view;
+use std::time::Duration;
+use terminal::TerminalGuard;
+pub use view::render;
 
-pub fn run_tui(processes: &[ProcessInfo]) -> Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// startup options for the TUI
+#[derive(Debug, Default)]
+pub struct TuiOptions {
+    /// filter applied when the TUI opens
+    pub filter: Option<String>,
+    /// hide processes without listening ports
+    pub listening_only: bool,
+    pub columns: Vec<Column>,
+    /// how often processes are reloaded; zero turns reloading off
+    pub refresh: Duration,
+    pub theme: Theme,
+    /// label of the snapshot being browsed; processes cannot be killed then
+    pub snapshot: Option<String>,
+    pub config: Config,
+    /// capture the mouse; off keeps the terminal's own text selection
+    pub mouse: bool,
+}
 
-    let bindings = KeyBindings::default();
-    let mut selected_index = 0;
-    let mut offset = 0;
-    let mut mode = Mode::Normal;
-    let mut filter_input = String::new();
-    let mut filtered_processes = processes.to_vec();
-    let mut clipboard_message = ClipboardMessage::default();
+/// run until the user quits; returns the signal that ended the TUI, if any,
+/// with the terminal already restored
+pub fn run_tui(processes: &[ProcessInfo], options: TuiOptions) -> Result<Option<i32>> {
+    // Ctrl-C and Ctrl-Z arrive as keys in raw mode; these come from `kill`
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGTSTP])?;
+    let guard = TerminalGuard::enter(options.mouse)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
 
-    loop {
-        terminal.draw(|f| {
-            draw_view(
-                f,
-                &filtered_processes,
-                selected_index,
-                offset,
-                &filter_input,
-                &mode,
-                &clipboard_message,
-            );
-        })?;
+    let mut app = App::new(processes.to_vec(), options, FilterHistory::load());
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key_event) = event::read()? {
-                match mode {
-                    Mode::Normal => match key_event {
-                        _ if bindings.is_quit(&key_event) => break,
-                        _ if bindings.is_down(&key_event) => {
-                            if selected_index + 1 < filtered_processes.len() {
-                                selected_index += 1;
-                                if selected_index >= offset + 20 {
-                                    offset += 1;
-                                }
-                            }
-                        }
-                        _ if bindings.is_up(&key_event) => {
-                            if selected_index > 0 {
-                                selected_index -= 1;
-                                if selected_index < offset {
-                                    offset = offset.saturating_sub(1);
-                                }
-                            }
-                        }
-                        _ if bindings.is_filter(&key_event) => {
-                            mode = Mode::FilterInput;
-                            filter_input.clear();
-                        }
-                        _ if bindings.is_detail(&key_event) => {
-                            mode = Mode::Detail;
-                        }
-                        _ if bindings.is_copy(&key_event) => {
-                            if let Some(proc) = filtered_processes.get(selected_index) {
-                                copy_pid_to_clipboard(proc, &mut clipboard_message);
-                            }
-                        }
-                        _ if bindings.is_kill(&key_event) => {
-                            mode = Mode::ConfirmKill;
-                        }
-                        _ => {}
-                    },
-                    Mode::FilterInput => match key_event.code {
-                        event::KeyCode::Esc | event::KeyCode::Enter => mode = Mode::Normal,
-                        event::KeyCode::Char(c) => {
-                            filter_input.push(c);
-                            filtered_processes = apply_filter(processes, &filter_input);
-                            selected_index = 0;
-                            offset = 0;
-                        }
-                        event::KeyCode::Backspace => {
-                            filter_input.pop();
-                            filtered_processes = apply_filter(processes, &filter_input);
-                            selected_index = 0;
-                            offset = 0;
-                        }
-                        _ => {}
-                    },
-                    Mode::Detail => match key_event.code {
-                        event::KeyCode::Esc | event::KeyCode::Char('q') | event::KeyCode::Tab => {
-                            mode = Mode::Normal
-                        }
-                        _ => {}
-                    },
-                    Mode::ConfirmKill => match key_event.code {
-                        event::KeyCode::Char('y') => {
-                            if let Some(proc) = filtered_processes.get(selected_index) {
-                                let result = nix::sys::signal::kill(
-                                    nix::unistd::Pid::from_raw(proc.pid),
-                                    nix::sys::signal::Signal::SIGKILL,
-                                );
+    let mut stopped_by = None;
+    'main: loop {
+        for signal in signals.pending() {
+            if signal != SIGTSTP {
+                stopped_by = Some(signal);
+                break 'main;
+            }
+            guard.suspend()?;
+            terminal.clear()?;
+        }
+
+        app.resize(terminal.size()?);
+        terminal.draw(|f| render(f, &app))?;
 
-                                clipboard_message.message = Some((
-                                    if result.is_ok() {
-                                        format!("✔ Killed process {}", proc.pid)
-                                    } else {
-                                        format!("✖ Failed to kill process {}", proc.pid)
-                                    },
-                                    std::time::Instant::now(),
-                                ));
-                            }
+        if app.refresh_due() {
+            reload(&mut app);
+        }
 
-                            filtered_processes = apply_filter(processes, &filter_input);
-                            mode = Mode::Normal;
-                            // break;
-                        }
-                        event::KeyCode::Char('n') | event::KeyCode::Esc => {
-                            mode = Mode::Normal;
-                        }
-                        _ => {
-                            mode = Mode::Normal;
-                        }
-                    },
+        if event::poll(Duration::from_millis(100))? {
+            let action = match event::read()? {
+                Event::Key(key) => app.handle_key(key),
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                _ => Action::None,
+            };
+
+            match action {
+                Action::None => {}
+                Action::Quit => break,
+                Action::Suspend => {
+                    guard.suspend()?;
+                    // the screen was left, so draw everything again
+                    terminal.clear()?;
+                }
+                Action::Reload => reload(&mut app),
+                Action::Kill(pid) => {
+                    let result = nix::sys::signal::kill(
+                        nix::unistd::Pid::from_raw(pid),
+                        nix::sys::signal::Signal::SIGKILL,
+                    );
+                    app.notify(if result.is_ok() {
+                        format!("✔ Killed process {pid}")
+                    } else {
+                        format!("✖ Failed to kill process {pid}")
+                    });
+                    if app.is_live() {
+                        reload(&mut app);
+                    }
+                }
+                Action::LoadDetails(pid) => app.set_details(pid, read_details(pid)),
+                Action::Copy { text, label } => {
+                    if copy_to_clipboard(&text) {
+                        app.notify(format!("✔ Copied {label} to clipboard"));
+                    }
                 }
+                Action::SaveColumns(columns) => app.notify(match save_columns(&columns) {
+                    Ok(()) => "✔ Saved columns to config".to_string(),
+                    Err(e) => format!("✖ Failed to save columns: {e}"),
+                }),
             }
         }
     }
 
-    disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
-    Ok(())
+    drop(terminal);
+    drop(guard);
+    Ok(stopped_by)
+}
+
+/// read the live processes again; `lsof` failing keeps the list on screen
+fn reload(app: &mut App) {
+    match get_all_processes() {
+        Ok(processes) => app.set_processes(processes),
+        Err(e) => app.reload_failed(&e.to_string()),
+    }
 }
@@ -1,14 +1,194 @@
+use crate::columns::Column;
+use std::fmt;
 use std::time::Instant;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mode {
     Normal,
     FilterInput,
     Detail,
+    CopyMenu,
     ConfirmKill,
+    Columns,
+    Help,
+}
+
+/// the tabs of the detail view; `Files` lists the open descriptors and `Maps`
+/// sums up the memory maps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailTab {
+    #[default]
+    Overview,
+    Environment,
+    Files,
+    Limits,
+    Sockets,
+    Maps,
+}
+
+impl DetailTab {
+    pub const ALL: [DetailTab; 6] = [
+        DetailTab::Overview,
+        DetailTab::Environment,
+        DetailTab::Files,
+        DetailTab::Limits,
+        DetailTab::Sockets,
+        DetailTab::Maps,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DetailTab::Overview => "Overview",
+            DetailTab::Environment => "Environment",
+            DetailTab::Files => "Files",
+            DetailTab::Limits => "Limits",
+            DetailTab::Sockets => "Sockets",
+            DetailTab::Maps => "Maps",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|&t| t == self).unwrap_or(0)
+    }
+
+    /// the next tab, or the previous one for a negative `delta`, wrapping around
+    pub fn cycle(self, delta: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        Self::ALL[(self.index() as isize + delta).rem_euclid(len) as usize]
+    }
+}
+
+/// where the details of the selected process are shown next to the list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaneLayout {
+    #[default]
+    ListOnly,
+    Right,
+    Bottom,
+}
+
+impl PaneLayout {
+    pub fn next(self) -> Self {
+        match self {
+            PaneLayout::ListOnly => PaneLayout::Right,
+            PaneLayout::Right => PaneLayout::Bottom,
+            PaneLayout::Bottom => PaneLayout::ListOnly,
+        }
+    }
+}
+
+impl fmt::Display for PaneLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PaneLayout::ListOnly => "list only",
+            PaneLayout::Right => "list + right pane",
+            PaneLayout::Bottom => "list + bottom pane",
+        })
+    }
+}
+
+/// the column the table is sorted by, set by clicking its title
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub column: Column,
+    pub descending: bool,
+}
+
+impl SortOrder {
+    /// sort by `column`, flipping the direction when it is already the sort column
+    pub fn toggle(current: Option<SortOrder>, column: Column) -> SortOrder {
+        match current {
+            Some(order) if order.column == column => SortOrder {
+                column,
+                descending: !order.descending,
+            },
+            _ => SortOrder {
+                column,
+                descending: false,
+            },
+        }
+    }
+}
+
+/// the selected row of the process list and the first row on screen
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListPosition {
+    pub selected: usize,
+    pub offset: usize,
+}
+
+impl ListPosition {
+    /// move the selection by `delta` rows, stopping at either end
+    pub fn move_by(&mut self, delta: isize, len: usize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+    }
+
+    pub fn select(&mut self, index: usize, len: usize) {
+        self.selected = index.min(len.saturating_sub(1));
+    }
+
+    /// adjust the offset so the selection is within the `rows` visible rows
+    pub fn scroll_into_view(&mut self, rows: usize, len: usize) {
+        let rows = rows.max(1);
+        self.selected = self.selected.min(len.saturating_sub(1));
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + rows {
+            self.offset = self.selected + 1 - rows;
+        }
+        // no blank rows at the bottom once the list got shorter
+        self.offset = self.offset.min(len.saturating_sub(rows));
+    }
 }
 
 #[derive(Default)]
 pub struct ClipboardMessage {
     pub message: Option<(String, Instant)>,
 }
+
+/// the column chooser popup: every column with whether it is shown
+#[derive(Debug, Default)]
+pub struct ColumnChooser {
+    pub items: Vec<(Column, bool)>,
+    pub cursor: usize,
+}
+
+impl ColumnChooser {
+    /// list the shown columns first, in their current order, then the hidden ones
+    pub fn new(current: &[Column]) -> Self {
+        let hidden = Column::ALL.into_iter().filter(|c| !current.contains(c));
+        let items = current
+            .iter()
+            .map(|&c| (c, true))
+            .chain(hidden.map(|c| (c, false)))
+            .collect();
+        Self { items, cursor: 0 }
+    }
+
+    pub fn up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        if self.cursor + 1 < self.items.len() {
+            self.cursor += 1;
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some((_, shown)) = self.items.get_mut(self.cursor) {
+            *shown = !*shown;
+        }
+    }
+
+    pub fn selected(&self) -> Vec<Column> {
+        self.items
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(c, _)| *c)
+            .collect()
+    }
+}
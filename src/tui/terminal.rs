@@ -0,0 +1,87 @@
+use crossterm::{
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use signal_hook::consts::SIGTSTP;
+use std::io;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// whether the terminal is in TUI mode, so it is restored only once
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+type PanicHook = dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send + 'static;
+
+/// puts the terminal into TUI mode and restores it when dropped, also when the
+/// TUI returns an error or panics
+pub struct TerminalGuard {
+    mouse: bool,
+    /// the panic hook installed before ours, put back when dropped
+    previous: Arc<PanicHook>,
+}
+
+impl TerminalGuard {
+    pub fn enter(mouse: bool) -> io::Result<Self> {
+        // restore before the default hook prints the message, so it is not
+        // lost on the alternate screen
+        let previous: Arc<PanicHook> = Arc::from(panic::take_hook());
+        let hook = Arc::clone(&previous);
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore();
+            hook(info);
+        }));
+
+        // built first, so that a failing setup is undone by `drop`, which
+        // leaves raw mode and puts the previous hook back
+        let guard = Self { mouse, previous };
+        setup(mouse)?;
+        Ok(guard)
+    }
+
+    /// give the terminal back and stop the process like Ctrl-Z in a shell,
+    /// setting it up again once the shell resumes it with `fg`
+    pub fn suspend(&self) -> io::Result<()> {
+        restore()?;
+        signal_hook::low_level::emulate_default_handler(SIGTSTP)?;
+        setup(self.mouse)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+        // back to the hook from before; it cannot be replaced while unwinding
+        if !std::thread::panicking() {
+            let previous = Arc::clone(&self.previous);
+            panic::set_hook(Box::new(move |info| previous(info)));
+        }
+    }
+}
+
+fn setup(mouse: bool) -> io::Result<()> {
+    ACTIVE.store(true, Ordering::SeqCst);
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// safe to call more than once, and when setup only got halfway; leaving the
+/// alternate screen twice would move the cursor back over the panic message
+fn restore() -> io::Result<()> {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        Show
+    )
+}
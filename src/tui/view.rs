@@ -1,56 +1,232 @@
+use super::app::App;
+use super::copy::{copy_text, CopyTarget};
 use super::detail::draw_process_detail;
-use super::state::{ClipboardMessage, Mode};
+use super::state::{ClipboardMessage, ColumnChooser, DetailTab, Mode, PaneLayout};
+use crate::bindings::{format_key, HelpSection, KeyBindings};
+use crate::columns::Column;
 use crate::process::ProcessInfo;
+use crate::theme::Theme;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Position, Rect},
     prelude::*,
-    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+    widgets::{
+        block::{Position as TitlePosition, Title},
+        Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, Tabs,
+    },
 };
 
-pub fn draw_view(
-    f: &mut Frame,
-    processes: &[ProcessInfo],
-    selected_index: usize,
-    offset: usize,
-    filter_input: &str,
-    mode: &Mode,
-    clipboard_message: &ClipboardMessage,
-) {
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // header
-            Constraint::Min(1),    // table
-            Constraint::Length(3), // message
-        ])
-        .split(f.size());
+/// draw the whole screen for the current state of `app`
+pub fn render(f: &mut Frame, app: &App) {
+    let layout = screen_layout(f.size());
+    let theme = &app.options.theme;
 
-    draw_header(f, layout[0], filter_input, mode);
-    draw_table(f, layout[1], processes, selected_index, offset);
-    draw_clipboard_message(f, layout[2], clipboard_message);
+    draw_header(f, layout[0], app);
+    let (list, pane) = list_and_pane(f.size(), app.pane);
+    if let Some(pane) = pane {
+        draw_detail_pane(f, pane, app.selected(), theme);
+    }
+    draw_table(f, list, app);
+    draw_clipboard_message(f, layout[2], &app.message, theme);
 
-    if matches!(mode, Mode::Detail) {
-        if let Some(proc) = processes.get(selected_index) {
-            draw_floating_detail(f, proc);
+    match app.mode {
+        Mode::Detail => {
+            if app.selected().is_some() {
+                draw_floating_detail(f, app);
+            }
         }
-    }
-    if matches!(mode, Mode::ConfirmKill) {
-        draw_kill_confirm(f);
+        Mode::CopyMenu => draw_copy_menu(f, app),
+        Mode::ConfirmKill => draw_kill_confirm(f, theme),
+        Mode::Columns => draw_column_chooser(f, app),
+        Mode::Help => draw_help(f, &app.help, app.help_scroll, theme),
+        Mode::Normal | Mode::FilterInput => {}
     }
 }
 
-fn draw_kill_confirm(f: &mut Frame) {
-    let area = f.size();
-    let width = 40;
-    let height = 5;
+/// a popup of the given size in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
-    let dialog_area = Rect::new(x, y, width, height);
+    Rect::new(x, y, width, height)
+}
+
+fn column_chooser_area(size: Rect, chooser: &ColumnChooser) -> Rect {
+    centered(size, 36, chooser.items.len() as u16 + 4).intersection(size)
+}
+
+/// the column chooser entry under the mouse
+pub fn column_chooser_item_at(
+    size: Rect,
+    chooser: &ColumnChooser,
+    x: u16,
+    y: u16,
+) -> Option<usize> {
+    let inner = column_chooser_area(size, chooser).inner(&Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    if !inner.contains(Position { x, y }) {
+        return None;
+    }
+    let index = (y - inner.y) as usize;
+    (index < chooser.items.len()).then_some(index)
+}
 
-    // 背景を塗りつぶしてゴミ表示を防ぐ
-    let clear = Paragraph::new("".repeat((width * height) as usize))
-        .style(Style::default().bg(Color::Black));
-    f.render_widget(clear, dialog_area);
+fn draw_column_chooser(f: &mut Frame, app: &App) {
+    let (chooser, theme) = (&app.column_chooser, &app.options.theme);
+    let dialog_area = column_chooser_area(f.size(), chooser);
+
+    f.render_widget(Clear, dialog_area);
+
+    let mut lines: Vec<Line> = chooser
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, (column, shown))| {
+            let mark = if *shown { "[x]" } else { "[ ]" };
+            let style = if i == chooser.cursor {
+                theme.selected_style()
+            } else {
+                Style::default()
+            };
+            Line::styled(format!("{mark} {}", column.title()), style)
+        })
+        .collect();
+    lines.push(Line::styled(
+        "space: toggle, enter: save",
+        Style::default().fg(theme.hint),
+    ));
+    if let Some(key) = app.options.config.keys.quit.first() {
+        lines.push(Line::styled(
+            format!("{}: cancel", format_key(key)),
+            Style::default().fg(theme.hint),
+        ));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Columns")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.popup_border)),
+        )
+        .style(Style::default().fg(theme.popup_fg).bg(theme.popup_bg));
+    f.render_widget(paragraph, dialog_area);
+}
+
+fn copy_menu_area(size: Rect) -> Rect {
+    centered(size, 64, CopyTarget::ALL.len() as u16 + 3).intersection(size)
+}
+
+/// the index of the copy menu entry under the mouse
+pub fn copy_menu_item_at(size: Rect, x: u16, y: u16) -> Option<usize> {
+    let inner = copy_menu_area(size).inner(&Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    if !inner.contains(Position { x, y }) {
+        return None;
+    }
+    let index = (y - inner.y) as usize;
+    (index < CopyTarget::ALL.len()).then_some(index)
+}
+
+fn draw_copy_menu(f: &mut Frame, app: &App) {
+    let theme = &app.options.theme;
+    let area = copy_menu_area(f.size());
+    let processes = app.copy_targets();
+    let label_width = CopyTarget::ALL
+        .iter()
+        .map(|t| t.label().len())
+        .max()
+        .unwrap_or(0);
+    // what each entry copies, cut to the popup
+    let preview_width = (area.width as usize).saturating_sub(label_width + 7);
+
+    f.render_widget(Clear, area);
+
+    let mut lines: Vec<Line> = CopyTarget::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &target)| {
+            let preview = match copy_text(target, &processes) {
+                Some(text) => {
+                    let mut lines = text.lines();
+                    let first = lines.next().unwrap_or_default();
+                    if lines.next().is_some() {
+                        format!("{first} …")
+                    } else {
+                        first.to_string()
+                    }
+                }
+                None => "-".to_string(),
+            };
+            let preview: String = preview.chars().take(preview_width).collect();
+            let style = if i == app.copy_cursor {
+                theme.selected_style()
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::raw(format!("{}  {:<label_width$}  ", i + 1, target.label())),
+                Span::styled(preview, Style::default().fg(theme.hint)),
+            ])
+            .style(style)
+        })
+        .collect();
+    lines.push(Line::styled(
+        "1-8 / enter: copy, esc: cancel",
+        Style::default().fg(theme.hint),
+    ));
+
+    let title = match processes.len() {
+        1 => "Copy".to_string(),
+        n => format!("Copy ({n} marked processes)"),
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.popup_border)),
+        )
+        .style(Style::default().fg(theme.popup_fg).bg(theme.popup_bg));
+    f.render_widget(paragraph, area);
+}
+
+const YES_BUTTON: &str = "[ Yes ]";
+const NO_BUTTON: &str = "[ No ]";
+
+fn kill_confirm_area(size: Rect) -> Rect {
+    centered(size, 40, 5)
+}
+
+/// the yes and no buttons on the last line of the kill confirmation
+fn kill_buttons(size: Rect) -> (Rect, Rect) {
+    let area = kill_confirm_area(size);
+    let (yes, no) = (YES_BUTTON.len() as u16, NO_BUTTON.len() as u16);
+    let x = area.x + (area.width.saturating_sub(yes + 3 + no)) / 2;
+    let y = area.y + area.height.saturating_sub(2);
+    (Rect::new(x, y, yes, 1), Rect::new(x + yes + 3, y, no, 1))
+}
+
+/// `Some(true)` for a click on yes, `Some(false)` for no
+pub fn kill_button_at(size: Rect, x: u16, y: u16) -> Option<bool> {
+    let (yes, no) = kill_buttons(size);
+    let position = Position { x, y };
+    if yes.contains(position) {
+        Some(true)
+    } else if no.contains(position) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn draw_kill_confirm(f: &mut Frame, theme: &Theme) {
+    let dialog_area = kill_confirm_area(f.size());
+
+    f.render_widget(Clear, dialog_area);
 
     let text = "Kill this process? (y/n)";
     let paragraph = Paragraph::new(text)
@@ -58,132 +234,497 @@ fn draw_kill_confirm(f: &mut Frame) {
             Block::default()
                 .title("Confirm Kill")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Red))
-                .style(Style::default().bg(Color::Black)),
+                .border_style(Style::default().fg(theme.danger))
+                .style(Style::default().bg(theme.popup_bg)),
         )
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(theme.popup_fg));
     f.render_widget(paragraph, dialog_area);
+
+    let (yes, no) = kill_buttons(f.size());
+    f.render_widget(
+        Paragraph::new(YES_BUTTON).style(Style::default().fg(theme.danger)),
+        yes,
+    );
+    f.render_widget(Paragraph::new(NO_BUTTON), no);
 }
 
-fn draw_header(f: &mut Frame, area: Rect, filter_input: &str, mode: &Mode) {
-    let text = match mode {
-        Mode::FilterInput => format!("Filter: {filter_input}"),
-        _ => "PortSage - TUI (↑/↓/j/k: move, enter: copy pid, tab: detail, q: quit)".to_string(),
+fn draw_header(f: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.options.theme;
+    let processes = app.shown();
+    let first_line = match app.mode {
+        Mode::FilterInput => format!("Filter: {}", app.filter_input),
+        _ => format!("PortSage - TUI ({})", key_hints(&app.options.config.keys)),
     };
-    let paragraph = Paragraph::new(text)
-        .style(Style::default().fg(Color::Cyan))
+    let shown = if app.listening_only {
+        "listening only"
+    } else {
+        "all processes"
+    };
+    let mut status = vec![Span::raw(format!(
+        "Showing: {shown} ({} rows)",
+        processes.len()
+    ))];
+    let exposed = processes.iter().filter(|p| p.is_exposed()).count();
+    if exposed > 0 {
+        status.push(Span::raw(" | "));
+        status.push(Span::styled(
+            format!("{exposed} exposed on all interfaces"),
+            Style::default().fg(theme.exposed),
+        ));
+    }
+    if !app.marked.is_empty() {
+        status.push(Span::raw(format!(" | {} marked", app.marked.len())));
+    }
+    if let Some(label) = &app.options.snapshot {
+        status.push(Span::raw(format!(" | Snapshot: {label} (read-only)")));
+    }
+    let paragraph = Paragraph::new(vec![Line::raw(first_line), Line::from(status)])
+        .style(Style::default().fg(theme.header))
         .block(Block::default().borders(Borders::BOTTOM));
     f.render_widget(paragraph, area);
 }
 
-fn draw_table(
-    f: &mut Frame,
-    area: Rect,
-    processes: &[ProcessInfo],
-    selected_index: usize,
-    offset: usize,
-) {
+/// the first key of the most used actions, for the header
+fn key_hints(bindings: &KeyBindings) -> String {
+    let first = |keys: &[crossterm::event::KeyEvent]| keys.first().map(format_key);
+    let movement = match (first(&bindings.up), first(&bindings.down)) {
+        (Some(up), Some(down)) => Some(format!("{up}/{down}")),
+        _ => None,
+    };
+    [
+        (movement, "move"),
+        (first(&bindings.filter), "filter"),
+        (first(&bindings.copy), "copy"),
+        (first(&bindings.detail), "detail"),
+        (first(&bindings.kill), "kill"),
+        (first(&bindings.help), "help"),
+        (first(&bindings.quit), "quit"),
+    ]
+    .into_iter()
+    .filter_map(|(key, action)| key.map(|k| format!("{k}: {action}")))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+fn draw_help(f: &mut Frame, sections: &[HelpSection], scroll: usize, theme: &Theme) {
+    let area = f.size();
+    let key_width = sections
+        .iter()
+        .flat_map(|s| s.entries.iter().map(|(k, _)| k.chars().count()))
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for section in sections {
+        if !lines.is_empty() {
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::styled(
+            section.mode,
+            Style::default()
+                .fg(theme.popup_border)
+                .add_modifier(Modifier::BOLD),
+        ));
+        for (keys, action) in &section.entries {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {keys:<key_width$}  "),
+                    Style::default().fg(theme.pid),
+                ),
+                Span::raw(action.clone()),
+            ]));
+        }
+    }
+
+    let width = (key_width as u16 + 44).min(area.width);
+    let height = help_height(lines.len(), area.height as usize) as u16;
+    let scroll = scroll.min(help_max_scroll(lines.len(), area.height as usize));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let help_area = Rect::new(x, y, width, height);
+
+    f.render_widget(Clear, help_area);
+    let paragraph = Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(
+            Block::default()
+                .title("Help (↑/↓ to scroll)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.popup_border)),
+        )
+        .style(Style::default().fg(theme.popup_fg).bg(theme.popup_bg));
+    f.render_widget(paragraph, help_area);
+}
+
+/// the help popup leaves a row free above and below
+fn help_height(lines: usize, rows: usize) -> usize {
+    (lines + 2).min(rows.saturating_sub(2))
+}
+
+/// how far the help can scroll before its last line reaches the bottom
+pub fn help_max_scroll(lines: usize, rows: usize) -> usize {
+    lines.saturating_sub(help_height(lines, rows).saturating_sub(2))
+}
+
+fn screen_layout(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // header
+            Constraint::Min(1),    // table
+            Constraint::Length(3), // message
+        ])
+        .split(area)
+}
+
+/// the terminal width the right pane needs, leaving the list room for its columns
+const RIGHT_PANE_MIN_WIDTH: u16 = 100;
+/// the detail lines plus the borders
+const BOTTOM_PANE_HEIGHT: u16 = 15;
+/// list rows kept above the bottom pane, including its borders and titles
+const BOTTOM_PANE_MIN_LIST: u16 = 8;
+
+/// the layout actually shown: one that does not fit the terminal falls back
+/// to the bottom pane, then to the list alone
+pub fn fitted_layout(size: Rect, pane: PaneLayout) -> PaneLayout {
+    let area = screen_layout(size)[1];
+    let fits_right = area.width >= RIGHT_PANE_MIN_WIDTH;
+    let fits_bottom = area.height >= BOTTOM_PANE_HEIGHT + BOTTOM_PANE_MIN_LIST;
+    match pane {
+        PaneLayout::Right if fits_right => PaneLayout::Right,
+        PaneLayout::Right | PaneLayout::Bottom if fits_bottom => PaneLayout::Bottom,
+        _ => PaneLayout::ListOnly,
+    }
+}
+
+/// the area of the process list and of the detail pane, if one is shown
+fn list_and_pane(size: Rect, pane: PaneLayout) -> (Rect, Option<Rect>) {
+    let area = screen_layout(size)[1];
+    match fitted_layout(size, pane) {
+        PaneLayout::Right => {
+            let [list, pane] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(area);
+            (list, Some(pane))
+        }
+        PaneLayout::Bottom => {
+            let [list, pane] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(BOTTOM_PANE_HEIGHT)])
+                    .areas(area);
+            (list, Some(pane))
+        }
+        PaneLayout::ListOnly => (area, None),
+    }
+}
+
+/// how many processes fit in the table: its area minus the borders and the title row
+pub fn table_rows(size: Rect, pane: PaneLayout) -> usize {
+    list_and_pane(size, pane).0.height.saturating_sub(3) as usize
+}
+
+/// spacing between table columns
+const COLUMN_SPACING: u16 = 2;
+
+/// column widths, with the copy mark and the exposure marker in front when
+/// they are shown
+fn table_widths(columns: &[Column], marks: bool, marker: bool) -> Vec<Constraint> {
+    marks
+        .then_some(Constraint::Length(1))
+        .into_iter()
+        .chain(marker.then_some(Constraint::Length(7)))
+        .chain(
+            columns
+                .iter()
+                .map(|c| c.width().map_or(Constraint::Min(10), Constraint::Length)),
+        )
+        .collect()
+}
+
+/// what a click in the process table landed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableHit {
+    Title(Column),
+    Row(usize),
+}
+
+/// map a mouse position to a column title or a process row
+pub fn table_hit(size: Rect, app: &App, x: u16, y: u16) -> Option<TableHit> {
+    let processes = app.shown();
+    let inner = list_and_pane(size, app.pane).0.inner(&Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    if !inner.contains(Position { x, y }) {
+        return None;
+    }
+    if y > inner.y {
+        let index = app.position.offset + (y - inner.y - 1) as usize;
+        return (index < processes.len()).then_some(TableHit::Row(index));
+    }
+
+    // the same layout the table widget uses; a gap belongs to the column before it
+    let marker = processes.iter().any(|p| p.is_exposed());
+    let marks = !app.marked.is_empty();
+    let cells = Layout::horizontal(table_widths(&app.columns, marks, marker))
+        .flex(Flex::Start)
+        .spacing(COLUMN_SPACING)
+        .split(inner);
+    let index = cells.iter().rposition(|cell| cell.x <= x)?;
+    let index = index.checked_sub(usize::from(marks) + usize::from(marker))?;
+    app.columns.get(index).copied().map(TableHit::Title)
+}
+
+fn draw_table(f: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.options.theme;
+    let processes = app.shown();
+    let (columns, sort, position, marked) = (&app.columns, app.sort, app.position, &app.marked);
+    let visible = area.height.saturating_sub(3) as usize;
+    let offset = position.offset;
+    let exposed_style = Style::default().fg(theme.exposed);
+    let loopback_style = Style::default().fg(theme.loopback);
+    // the marker column is only shown when something is exposed
+    let marker = processes.iter().any(|p| p.is_exposed());
+    let marks = !marked.is_empty();
+
     let rows = processes
         .iter()
         .skip(offset)
-        .take((area.height - 2) as usize)
+        .take(visible)
         .enumerate()
         .map(|(i, p)| {
-            let style = if i + offset == selected_index {
-                Style::default().bg(Color::DarkGray)
+            let style = if i + offset == position.selected {
+                theme.selected_style()
             } else {
                 Style::default()
             };
-            Row::new(vec![
-                Cell::from(p.pid.to_string()).style(Style::default().fg(Color::Green)),
-                Cell::from(p.name.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from(
-                    p.ports
-                        .iter()
-                        .map(|p| p.to_string())
-                        .collect::<Vec<_>>()
-                        .join(", "),
-                )
-                .style(Style::default().fg(Color::Yellow)),
-                Cell::from(p.cmd.join(" ")).style(Style::default().fg(Color::Blue)),
-            ])
-            .style(style)
+            let exposed = p.is_exposed();
+            let loopback = !exposed && p.is_loopback_only();
+            let cells = columns.iter().map(|c| {
+                let style = if exposed {
+                    column_style(*c, theme).patch(exposed_style)
+                } else if loopback && *c == Column::Ports {
+                    column_style(*c, theme).patch(loopback_style)
+                } else {
+                    column_style(*c, theme)
+                };
+                Cell::from(c.value(p)).style(style)
+            });
+            let copy_mark = marked.contains(&p.pid).then_some("●");
+            let mark = if exposed {
+                Cell::from("exposed").style(exposed_style)
+            } else if loopback {
+                Cell::from("local").style(loopback_style)
+            } else {
+                Cell::from("")
+            };
+            let front = marks
+                .then(|| Cell::from(copy_mark.unwrap_or_default()))
+                .into_iter()
+                .chain(marker.then_some(mark));
+            Row::new(front.chain(cells)).style(style)
         });
 
-    let table = Table::new(
-        rows,
-        [
-            Constraint::Length(8),  // PID
-            Constraint::Length(20), // Name
-            Constraint::Length(10), // Ports
-            Constraint::Min(10),    // Command
-        ],
-    )
-    .header(
-        Row::new(vec!["PID", "Name", "Ports", "Command"]).style(Style::default().fg(Color::Yellow)),
-    )
-    .block(Block::default().borders(Borders::ALL))
-    .column_spacing(2);
+    let titles = marks
+        .then(String::new)
+        .into_iter()
+        .chain(marker.then(String::new))
+        .chain(columns.iter().map(|&c| match sort {
+            Some(order) if order.column == c => {
+                let arrow = if order.descending { "▼" } else { "▲" };
+                format!("{} {arrow}", c.title())
+            }
+            _ => c.title().to_string(),
+        }));
+
+    let table = Table::new(rows, table_widths(columns, marks, marker))
+        .header(Row::new(titles).style(Style::default().fg(theme.table_header)))
+        .block(Block::default().borders(Borders::ALL))
+        .column_spacing(COLUMN_SPACING);
 
     f.render_widget(table, area);
+
+    if processes.len() > visible {
+        let mut state = ScrollbarState::new(processes.len().saturating_sub(visible))
+            .position(offset)
+            .viewport_content_length(visible);
+        // drawn over the right border, below the title row
+        let track = area.inner(&Margin {
+            vertical: 1,
+            horizontal: 0,
+        });
+        let track = Rect {
+            y: track.y + 1,
+            height: track.height.saturating_sub(1),
+            ..track
+        };
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            track,
+            &mut state,
+        );
+    }
+}
+
+fn column_style(column: Column, theme: &Theme) -> Style {
+    match column {
+        Column::Pid => Style::default().fg(theme.pid),
+        Column::Name => Style::default().fg(theme.name).add_modifier(Modifier::BOLD),
+        Column::Ports => Style::default().fg(theme.ports),
+        Column::Cmd => Style::default().fg(theme.command),
+        _ => Style::default(),
+    }
 }
 
-fn draw_clipboard_message(f: &mut Frame, area: Rect, clipboard_message: &ClipboardMessage) {
+fn draw_clipboard_message(
+    f: &mut Frame,
+    area: Rect,
+    clipboard_message: &ClipboardMessage,
+    theme: &Theme,
+) {
     if let Some((msg, ts)) = &clipboard_message.message {
         if ts.elapsed().as_secs_f32() < 2.0 {
             let p = Paragraph::new(msg.clone())
-                .style(Style::default().fg(Color::Green))
+                .style(Style::default().fg(theme.message))
                 .block(Block::default().borders(Borders::TOP));
             f.render_widget(p, area);
         }
     }
 }
 
-fn draw_floating_detail(f: &mut Frame, proc: &ProcessInfo) {
-    let area = f.size();
-    let width = area.width.saturating_sub(10).min(100);
-    let height = 13;
-    let x = (area.width.saturating_sub(width)) / 2;
-    let y = (area.height.saturating_sub(height)) / 2;
-    let detail_area = Rect::new(x, y, width, height);
-
-    // 背景をクリアして透けを防ぐ
-    f.render_widget(Clear, detail_area);
-
-    let content = vec![
-        format!("PID: {}", proc.pid),
-        format!("Name: {}", proc.name),
-        format!("Status: {}", proc.status),
-        format!("CPU Usage: {:.2}%", proc.cpu_usage),
-        format!("Memory: {} KB", proc.memory),
-        format!("Virtual Memory: {} KB", proc.virtual_memory),
-        format!(
-            "Parent PID: {}",
-            proc.parent_pid.map_or("N/A".into(), |p| p.to_string())
-        ),
-        format!("Start Time: {}", proc.start_time),
-        format!("Exe: {}", proc.exe),
-        format!("CWD: {}", proc.cwd),
-        format!(
-            "Ports: {}",
-            proc.ports
-                .iter()
-                .map(|p| p.to_string())
-                .collect::<Vec<_>>()
-                .join(", ")
+fn draw_detail_pane(f: &mut Frame, area: Rect, proc: Option<&ProcessInfo>, theme: &Theme) {
+    match proc {
+        Some(proc) => f.render_widget(draw_process_detail(proc, theme), area),
+        None => f.render_widget(
+            Block::default()
+                .title("Process Detail")
+                .borders(Borders::ALL),
+            area,
         ),
-        format!("Cmd: {}", proc.cmd.join(" ")),
+    }
+}
+
+/// the detail popup, as tall as the terminal allows
+fn detail_area(size: Rect) -> Rect {
+    let width = size.width.saturating_sub(10).min(100);
+    let height = size.height.saturating_sub(4).max(16).min(size.height);
+    centered(size, width, height)
+}
+
+/// the popup without its borders, split into the tab titles and the text
+fn detail_parts(size: Rect) -> (Rect, Rect) {
+    let inner = detail_area(size).inner(&Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let [tabs, body] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    (tabs, body)
+}
+
+/// how many lines of a detail tab are visible
+pub fn detail_rows(size: Rect) -> usize {
+    detail_parts(size).1.height as usize
+}
+
+/// what a click in the detail view landed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailHit {
+    Tab(DetailTab),
+    Inside,
+}
+
+pub fn detail_hit(size: Rect, x: u16, y: u16) -> Option<DetailHit> {
+    if !detail_area(size).contains(Position { x, y }) {
+        return None;
+    }
+    let (tabs, _) = detail_parts(size);
+    if y != tabs.y {
+        return Some(DetailHit::Inside);
+    }
+    // laid out like the tabs widget: a space on each side and a divider between
+    let mut left = tabs.x;
+    for tab in DetailTab::ALL {
+        let width = tab.title().len() as u16 + 2;
+        if (left..left + width).contains(&x) {
+            return Some(DetailHit::Tab(tab));
+        }
+        left += width + 1;
+    }
+    Some(DetailHit::Inside)
+}
+
+/// the footer of the detail view, with the keys in effect for scrolling and copying
+fn detail_hints(bindings: &KeyBindings) -> String {
+    let first = |keys: &[crossterm::event::KeyEvent]| keys.first().map(format_key);
+    let scroll = match (first(&bindings.up), first(&bindings.down)) {
+        (Some(up), Some(down)) => Some(format!("{up}/{down}")),
+        _ => None,
+    };
+    [
+        (Some("←/→".to_string()), "tab"),
+        (scroll, "scroll"),
+        (first(&bindings.copy), "copy"),
     ]
-    .join("\n");
+    .into_iter()
+    .filter_map(|(key, action)| key.map(|k| format!("{k}: {action}")))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
 
-    let paragraph = Paragraph::new(content)
-        .block(
-            Block::default()
-                .title("Process Detail")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow)),
-        )
-        .style(Style::default().fg(Color::White).bg(Color::Black));
+fn draw_floating_detail(f: &mut Frame, app: &App) {
+    let theme = &app.options.theme;
+    let area = detail_area(f.size());
+    let (tabs_area, body) = detail_parts(f.size());
 
-    f.render_widget(paragraph, detail_area);
+    // 背景をクリアして透けを防ぐ
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Block::default()
+            .title("Process Detail")
+            .title(
+                Title::from(detail_hints(&app.options.config.keys))
+                    .position(TitlePosition::Bottom)
+                    .alignment(Alignment::Right),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.popup_border))
+            .style(Style::default().fg(theme.popup_fg).bg(theme.popup_bg)),
+        area,
+    );
+
+    let titles: Vec<&str> = DetailTab::ALL.into_iter().map(DetailTab::title).collect();
+    f.render_widget(
+        Tabs::new(titles)
+            .select(app.detail_tab.index())
+            .highlight_style(theme.selected_style()),
+        tabs_area,
+    );
+
+    let lines = app.detail_lines();
+    let visible = body.height as usize;
+    let scroll = app.detail_scroll.min(lines.len().saturating_sub(visible));
+    f.render_widget(
+        Paragraph::new(lines.join("\n")).scroll((scroll as u16, 0)),
+        body,
+    );
+
+    if lines.len() > visible {
+        let mut state = ScrollbarState::new(lines.len().saturating_sub(visible))
+            .position(scroll)
+            .viewport_content_length(visible);
+        // over the right border, next to the text
+        let track = Rect {
+            x: area.right().saturating_sub(1),
+            width: 1,
+            ..body
+        };
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            track,
+            &mut state,
+        );
+    }
 }
@@ -0,0 +1,57 @@
+use anyhow::{bail, Result};
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// the state `wait` blocks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitFor {
+    Listening,
+    Free,
+}
+
+impl fmt::Display for WaitFor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitFor::Listening => write!(f, "listening"),
+            WaitFor::Free => write!(f, "free"),
+        }
+    }
+}
+
+/// poll `port_owner` until the port reaches the wanted state.
+/// returns the PID holding the port, or `None` once it has been released;
+/// an error from `port_owner` stops the wait.
+pub fn wait_for_port<F>(
+    port: u16,
+    until: WaitFor,
+    timeout: Option<Duration>,
+    interval: Duration,
+    mut port_owner: F,
+) -> Result<Option<u32>>
+where
+    F: FnMut(u16) -> Result<Option<u32>>,
+{
+    let started = Instant::now();
+
+    loop {
+        match (until, port_owner(port)?) {
+            (WaitFor::Listening, Some(pid)) => return Ok(Some(pid)),
+            (WaitFor::Free, None) => return Ok(None),
+            _ => {}
+        }
+
+        let mut pause = interval;
+        if let Some(timeout) = timeout {
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                bail!(
+                    "timed out after {:?} waiting for port {port} to be {until}",
+                    timeout
+                );
+            }
+            pause = pause.min(timeout - elapsed);
+        }
+        thread::sleep(pause);
+    }
+}
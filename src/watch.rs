@@ -0,0 +1,121 @@
+use crate::process::ProcessInfo;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// a port held by a process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listener {
+    pub port: u16,
+    pub pid: i32,
+    pub name: String,
+    pub cwd: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Open,
+    Close,
+}
+
+/// a listener that appeared or disappeared between two polls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WatchEvent {
+    pub event: ChangeKind,
+    pub port: u16,
+    pub pid: i32,
+    pub name: String,
+    pub cwd: String,
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+impl fmt::Display for WatchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.event {
+            ChangeKind::Open => write!(
+                f,
+                "+ {} {}[{}] {}",
+                self.port,
+                self.name,
+                self.pid,
+                shorten_home(&self.cwd)
+            ),
+            ChangeKind::Close => write!(f, "- {} {}[{}]", self.port, self.name, self.pid),
+        }
+    }
+}
+
+pub type ListenerMap = BTreeMap<(u16, i32), Listener>;
+
+/// index the listeners of a process list by port and PID
+pub fn listeners(processes: &[ProcessInfo]) -> ListenerMap {
+    processes
+        .iter()
+        .flat_map(|p| {
+            p.ports.iter().map(|&port| {
+                (
+                    (port, p.pid),
+                    Listener {
+                        port,
+                        pid: p.pid,
+                        name: p.name.clone(),
+                        cwd: p.cwd.clone(),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+/// listeners closed since `before` followed by the ones opened, each ordered by port
+pub fn diff_listeners(
+    before: &ListenerMap,
+    after: &ListenerMap,
+    timestamp: u64,
+) -> Vec<WatchEvent> {
+    let event = |kind, l: &Listener| WatchEvent {
+        event: kind,
+        port: l.port,
+        pid: l.pid,
+        name: l.name.clone(),
+        cwd: l.cwd.clone(),
+        timestamp,
+    };
+
+    let closed = before
+        .iter()
+        .filter(|(key, _)| !after.contains_key(key))
+        .map(|(_, l)| event(ChangeKind::Close, l));
+    let opened = after
+        .iter()
+        .filter(|(key, _)| !before.contains_key(key))
+        .map(|(_, l)| event(ChangeKind::Open, l));
+
+    closed.chain(opened).collect()
+}
+
+/// processes in `before` that are gone from `after`.
+/// the start time is compared too, so a reused PID still counts as an exit.
+pub fn exited_processes<'a>(
+    before: &'a [ProcessInfo],
+    after: &[ProcessInfo],
+) -> Vec<&'a ProcessInfo> {
+    let alive: HashSet<(i32, u64)> = after.iter().map(|p| (p.pid, p.start_time)).collect();
+    before
+        .iter()
+        .filter(|p| !alive.contains(&(p.pid, p.start_time)))
+        .collect()
+}
+
+/// replace the home directory prefix with `~`
+pub fn shorten_home(path: &str) -> String {
+    match std::env::var("HOME") {
+        Ok(home) if !home.is_empty() && home != "/" => match path.strip_prefix(&home) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("~{rest}"),
+            _ => path.to_string(),
+        },
+        _ => path.to_string(),
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::process;
+use portsage::audit::{audit_processes, Bind, Policy, ViolationKind};
+use portsage::port::Binding;
+
+const POLICY: &str = r#"
+[[listener]]
+port = 5432
+process = "postgres*"
+bind = "loopback"
+
+[[listener]]
+port = 8080
+process = "node"
+
+[[listener]]
+port = 6379
+process = "redis-server"
+
+[[listener]]
+port = 9000
+optional = true
+"#;
+
+#[test]
+fn test_parse_policy() {
+    let policy = Policy::parse(POLICY).unwrap();
+
+    assert_eq!(policy.listeners.len(), 4);
+    assert_eq!(policy.listeners[0].bind, Bind::Loopback);
+    assert_eq!(policy.listeners[1].bind, Bind::Any);
+    assert!(policy.listeners[3].optional);
+    assert!(Policy::parse("[[listener]]\nport = 1\nproces = \"x\"\n").is_err());
+}
+
+#[test]
+fn test_audit_processes() {
+    let policy = Policy::parse(POLICY).unwrap();
+    let processes = [
+        process(987, "postgres").listen("*", 5432).build(),
+        process(1234, "node").listen("*", 8080).build(),
+        process(2000, "python3").listen("127.0.0.1", 8000).build(),
+    ];
+
+    let violations = audit_processes(&policy, &processes);
+    let lines: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+
+    assert_eq!(
+        lines,
+        [
+            "bind        5432   postgres[987] on *:5432, expected loopback",
+            "missing     6379   redis-server",
+            "unexpected  8000   python3[2000] on 127.0.0.1:8000",
+        ]
+    );
+    assert_eq!(violations[0].kind, ViolationKind::Bind);
+}
+
+#[test]
+fn test_audit_clean() {
+    let policy = Policy::parse(POLICY).unwrap();
+    let processes = [
+        process(987, "postgres")
+            .listen("127.0.0.1", 5432)
+            .listen("::1", 5432)
+            .build(),
+        process(1234, "node").listen("*", 8080).build(),
+        process(42, "redis-server")
+            .listen("127.0.0.1", 6379)
+            .build(),
+    ];
+
+    assert!(audit_processes(&policy, &processes).is_empty());
+}
+
+#[test]
+fn test_bind_address() {
+    let exposed = Binding {
+        addr: "*".into(),
+        port: 80,
+    };
+    let lan = Binding {
+        addr: "192.168.1.5".into(),
+        port: 80,
+    };
+
+    assert!(Bind::from("0.0.0.0".to_string()).allows(&exposed));
+    assert!(!Bind::from("0.0.0.0".to_string()).allows(&lan));
+    assert!(Bind::from("192.168.1.5".to_string()).allows(&lan));
+    assert!(!Bind::Loopback.allows(&lan));
+}
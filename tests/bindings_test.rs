@@ -0,0 +1,97 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use portsage::bindings::{format_key, parse_key, KeyBindings};
+
+#[test]
+fn test_parse_key() {
+    let key = |code, modifiers| KeyEvent::new(code, modifiers);
+
+    assert_eq!(
+        parse_key("q"),
+        Ok(key(KeyCode::Char('q'), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        parse_key("G"),
+        Ok(key(KeyCode::Char('G'), KeyModifiers::SHIFT))
+    );
+    assert_eq!(
+        parse_key("-"),
+        Ok(key(KeyCode::Char('-'), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        parse_key("ctrl-k"),
+        Ok(key(KeyCode::Char('k'), KeyModifiers::CONTROL))
+    );
+    assert_eq!(
+        parse_key("Ctrl-Alt-PageDown"),
+        Ok(key(
+            KeyCode::PageDown,
+            KeyModifiers::CONTROL | KeyModifiers::ALT
+        ))
+    );
+    assert_eq!(
+        parse_key("shift-tab"),
+        Ok(key(KeyCode::BackTab, KeyModifiers::NONE))
+    );
+    assert_eq!(parse_key("f5"), Ok(key(KeyCode::F(5), KeyModifiers::NONE)));
+    assert_eq!(
+        parse_key("space"),
+        Ok(key(KeyCode::Char(' '), KeyModifiers::NONE))
+    );
+
+    assert!(parse_key("hyper-k").is_err());
+    assert!(parse_key("pagedwn").is_err());
+    assert!(parse_key("").is_err());
+}
+
+#[test]
+fn test_format_key_round_trips() {
+    for text in [
+        "q",
+        "ctrl-k",
+        "esc",
+        "pagedown",
+        "shift-tab",
+        "space",
+        "f12",
+        "alt-enter",
+        ":",
+    ] {
+        assert_eq!(format_key(&parse_key(text).unwrap()), text);
+    }
+
+    for (_, _, keys) in KeyBindings::default().actions() {
+        for key in keys {
+            assert_eq!(parse_key(&format_key(key)).as_ref(), Ok(key));
+        }
+    }
+}
+
+#[test]
+fn test_help_sections_follow_bindings() {
+    let bindings = KeyBindings {
+        kill: vec![parse_key("ctrl-k").unwrap()],
+        ..KeyBindings::default()
+    };
+
+    let sections = bindings.help_sections();
+    let modes: Vec<&str> = sections.iter().map(|s| s.mode).collect();
+    assert_eq!(
+        modes,
+        [
+            "Normal",
+            "Filter",
+            "Detail",
+            "Copy",
+            "Confirm kill",
+            "Columns",
+            "Help",
+            "Always"
+        ]
+    );
+
+    let normal = &sections[0].entries;
+    assert_eq!(normal.len(), bindings.actions().len());
+    assert!(normal.contains(&("ctrl-k".to_string(), "Kill process (confirm)".to_string())));
+    assert!(normal.contains(&("?".to_string(), "Show this help".to_string())));
+    assert_eq!(sections[2].entries[0].0, "q / esc / tab");
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::process;
+use portsage::check::{check_ports, exit_code, EXIT_ALL_FREE, EXIT_IN_USE};
+use std::collections::HashMap;
+
+#[test]
+fn test_check_ports() {
+    let processes = vec![process(1234, "node")
+        .cmd(&["node", "server.js"])
+        .ports(&[8080])
+        .build()];
+    let port_map = HashMap::from([(8080, 1234)]);
+
+    let statuses = check_ports(&[8080, 3000], &port_map, &processes);
+
+    assert!(statuses[0].in_use);
+    assert_eq!(statuses[0].name.as_deref(), Some("node"));
+    assert_eq!(
+        statuses[0].to_string(),
+        "8080   in use by node[1234]  node server.js"
+    );
+    assert!(!statuses[1].in_use);
+    assert_eq!(statuses[1].to_string(), "3000   free");
+    assert_eq!(exit_code(&statuses), EXIT_IN_USE);
+    assert_eq!(exit_code(&statuses[1..]), EXIT_ALL_FREE);
+}
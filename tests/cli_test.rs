@@ -1,5 +1,6 @@
 use clap::Parser;
-use portsage::cli::Cli;
+use portsage::cli::{parse_duration, parse_interval, Cli, Command, SelectArgs};
+use portsage::config::Config;
 
 #[test]
 fn test_parse_filter_argument() {
@@ -24,3 +25,211 @@ fn test_parse_all_arguments() {
     // assert!(cli.json);
     assert_eq!(cli.kill, Some(123));
 }
+
+#[test]
+fn test_parse_listening_flag() {
+    let cli = Cli::parse_from(["test", "--cli", "--listening"]);
+    assert!(cli.listening);
+    assert!(!Cli::parse_from(["test"]).listening);
+}
+
+#[test]
+fn test_parse_all_flag() {
+    let cli = Cli::parse_from(["test", "list", "--all"]);
+    let Some(Command::List(args)) = cli.command else {
+        panic!("expected list subcommand");
+    };
+    assert!(args.select.all && !args.select.listening);
+    assert!(Cli::try_parse_from(["test", "tui", "--all", "--listening"]).is_err());
+}
+
+#[test]
+fn test_parse_free_port_subcommand() {
+    let cli = Cli::parse_from(["test", "free-port", "8000-8100", "-n", "2", "--verify"]);
+    let Some(Command::FreePort(args)) = cli.command else {
+        panic!("expected free-port subcommand");
+    };
+    assert_eq!(args.range.start, 8000);
+    assert_eq!(args.range.end, 8100);
+    assert_eq!(args.count, 2);
+    assert_eq!(args.verify, Some("127.0.0.1".parse().unwrap()));
+}
+
+#[test]
+fn test_free_port_verify_before_range() {
+    // the range is not taken as the address to verify on
+    let cli = Cli::parse_from(["test", "free-port", "--verify", "18080-18085"]);
+    let Some(Command::FreePort(args)) = cli.command else {
+        panic!("expected free-port subcommand");
+    };
+    assert_eq!(args.range.start, 18080);
+    assert_eq!(args.range.end, 18085);
+    assert_eq!(args.verify, Some("127.0.0.1".parse().unwrap()));
+
+    let cli = Cli::parse_from(["test", "free-port", "--verify=0.0.0.0"]);
+    let Some(Command::FreePort(args)) = cli.command else {
+        panic!("expected free-port subcommand");
+    };
+    assert_eq!(args.verify, Some("0.0.0.0".parse().unwrap()));
+}
+
+#[test]
+fn test_parse_duration() {
+    use std::time::Duration;
+
+    assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+    assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+    assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+    assert_eq!(parse_duration("5").unwrap(), Duration::from_secs(5));
+    assert!(parse_duration("10x").is_err());
+    assert!(parse_duration("s").is_err());
+    assert!(parse_duration("999999999999999999h").is_err());
+    assert!(parse_duration("999999999999999999m").is_err());
+}
+
+#[test]
+fn test_zero_interval_is_rejected() {
+    assert!(parse_interval("0s").is_err());
+    assert!(parse_interval("0ms").is_err());
+    assert_eq!(
+        parse_interval("1ms").unwrap(),
+        std::time::Duration::from_millis(1)
+    );
+    assert!(Cli::try_parse_from(["test", "watch", "--interval", "0s"]).is_err());
+    assert!(Cli::try_parse_from(["test", "wait", "-p", "80", "--interval", "0"]).is_err());
+    // a zero timeout or refresh still means "off"
+    assert!(Cli::try_parse_from(["test", "wait", "-p", "80", "--timeout", "0"]).is_ok());
+    assert!(Cli::try_parse_from(["test", "tui", "--refresh", "0"]).is_ok());
+}
+
+#[test]
+fn test_wait_flags_conflict() {
+    assert!(Cli::try_parse_from(["test", "wait", "-p", "80", "--listening", "--free"]).is_err());
+}
+
+#[test]
+fn test_default_command_is_tui() {
+    let command = Cli::parse_from(["test"]).into_command();
+    assert!(matches!(command, Command::Tui(_)));
+}
+
+#[test]
+fn test_deprecated_flags_map_to_subcommands() {
+    let cli = Cli::parse_from(["test", "--cli", "--filter", "node", "--port", "3000"]);
+    assert_eq!(cli.deprecated_flags().len(), 3);
+    let Command::List(args) = cli.into_command() else {
+        panic!("expected list command");
+    };
+    assert_eq!(args.select.filter.as_deref(), Some("node"));
+    assert_eq!(args.port, Some(3000));
+
+    let command = Cli::parse_from(["test", "--kill", "123"]).into_command();
+    assert!(matches!(command, Command::Kill(args) if args.pid == Some(123)));
+}
+
+#[test]
+fn test_deprecated_flags_for_the_tui() {
+    // without --cli the flags select processes in the TUI
+    let cli = Cli::parse_from(["test", "--filter", "node", "--listening"]);
+    assert_eq!(
+        cli.deprecated_flags(),
+        [
+            ("--filter", "portsage tui --filter"),
+            ("--listening", "portsage tui --listening")
+        ]
+    );
+
+    let cli = Cli::parse_from(["test", "--port", "3000"]);
+    assert!(cli.check_deprecated_flags().is_ok());
+    let Command::Tui(args) = cli.into_command() else {
+        panic!("expected tui command");
+    };
+    assert_eq!(args.select.filter.as_deref(), Some("port:3000"));
+
+    let cli = Cli::parse_from(["test", "--port", "3000", "--filter", "node"]);
+    assert!(cli.check_deprecated_flags().is_err());
+    let cli = Cli::parse_from(["test", "--cli", "--port", "3000", "--filter", "node"]);
+    assert!(cli.check_deprecated_flags().is_ok());
+}
+
+#[test]
+fn test_deprecated_json_lists_processes() {
+    let cli = Cli::parse_from(["test", "--json", "--listening"]);
+    assert_eq!(
+        cli.deprecated_flags(),
+        [
+            ("--json", "portsage list --json"),
+            ("--listening", "portsage list --listening")
+        ]
+    );
+    let Command::List(args) = cli.into_command() else {
+        panic!("expected list command");
+    };
+    assert!(args.json);
+    assert!(args.select.listening);
+
+    // --port and --filter together are fine once the output is a list
+    let cli = Cli::parse_from(["test", "--json", "--port", "3000", "--filter", "node"]);
+    assert!(cli.check_deprecated_flags().is_ok());
+    assert!(Cli::try_parse_from(["test", "--tui", "--json"]).is_err());
+}
+
+#[test]
+fn test_deprecated_flags_conflict_with_subcommands() {
+    let cli = Cli::parse_from(["test", "--filter", "x", "list"]);
+    assert!(cli.check_deprecated_flags().is_err());
+    // global options are fine on either side
+    let cli = Cli::parse_from(["test", "--color", "never", "list", "--filter", "x"]);
+    assert!(cli.check_deprecated_flags().is_ok());
+}
+
+#[test]
+fn test_parse_list_subcommand() {
+    let cli = Cli::parse_from(["test", "list", "--listening", "-p", "5432", "--json"]);
+    assert!(cli.deprecated_flags().is_empty());
+    let Some(Command::List(args)) = cli.command else {
+        panic!("expected list subcommand");
+    };
+    assert!(args.select.listening);
+    assert_eq!(args.port, Some(5432));
+    assert!(args.json);
+}
+
+#[test]
+fn test_kill_requires_pid_or_port() {
+    assert!(Cli::try_parse_from(["test", "kill"]).is_err());
+    assert!(Cli::try_parse_from(["test", "kill", "42", "--port", "80"]).is_err());
+    assert!(Cli::try_parse_from(["test", "kill", "--port", "80"]).is_ok());
+}
+
+#[test]
+fn test_one_filter_rule_for_list_and_tui() {
+    let config = Config::parse(
+        "[defaults]\npreset = \"db\"\n[presets.db]\nfilter = \"postgres\"\n[presets.web]\nfilter = \"node\"\n",
+    )
+    .unwrap();
+    let select = |args: &[&str]| -> SelectArgs {
+        match Cli::parse_from(args).command {
+            Some(Command::List(args)) => args.select,
+            Some(Command::Tui(args)) => args.select,
+            command => panic!("unexpected {command:?}"),
+        }
+    };
+    let query = |args: &[&str]| select(args).query(&config).unwrap();
+
+    for command in ["list", "tui"] {
+        assert_eq!(query(&["test", command]).as_deref(), Some("postgres"));
+        assert_eq!(
+            query(&["test", command, "--preset", "web"]).as_deref(),
+            Some("node")
+        );
+        // --filter replaces the default preset instead of narrowing it
+        assert_eq!(
+            query(&["test", command, "--filter", "redis"]).as_deref(),
+            Some("redis")
+        );
+        assert!(
+            Cli::try_parse_from(["test", command, "--preset", "web", "--filter", "x"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::{postgres, process};
+use portsage::columns::{column_rows, Column};
+use portsage::config::{save_columns_to, Config};
+use portsage::tui::SortOrder;
+use std::cmp::Ordering;
+
+#[test]
+fn test_parse_columns() {
+    assert_eq!("pid".parse::<Column>().unwrap(), Column::Pid);
+    assert_eq!("Command".parse::<Column>().unwrap(), Column::Cmd);
+    assert_eq!("memory".parse::<Column>().unwrap(), Column::Mem);
+    assert!("pids".parse::<Column>().is_err());
+}
+
+#[test]
+fn test_column_rows() {
+    let columns = [
+        Column::Pid,
+        Column::User,
+        Column::Ports,
+        Column::Cpu,
+        Column::Mem,
+        Column::Start,
+        Column::Cmd,
+    ];
+    let (headers, rows) = column_rows(&[postgres().build()], &columns);
+
+    assert_eq!(
+        headers,
+        ["PID", "User", "Ports", "CPU%", "Memory", "Started", "Command"]
+    );
+    assert_eq!(
+        rows[0],
+        [
+            "42",
+            "postgres",
+            "5432, 5433",
+            "1.2",
+            "3.0 MiB",
+            "2023-11-14 22:13:20",
+            "postgres -D /data"
+        ]
+    );
+}
+
+#[test]
+fn test_columns_in_config() {
+    let config = Config::parse("columns = [\"pid\", \"command\", \"mem\"]\n").unwrap();
+    assert_eq!(
+        config.columns,
+        Some(vec![Column::Pid, Column::Cmd, Column::Mem])
+    );
+}
+
+#[test]
+fn test_save_columns_keeps_other_settings() {
+    let path = std::env::temp_dir().join(format!("portsage-columns-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "# my presets\n[presets.db]\nfilter = \"port:5432\"\n",
+    )
+    .unwrap();
+
+    save_columns_to(&path, &[Column::Pid, Column::Ports]).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("# my presets"));
+    let config = Config::parse(&text).unwrap();
+    assert_eq!(config.columns, Some(vec![Column::Pid, Column::Ports]));
+    assert_eq!(config.preset("db").unwrap().filter, "port:5432");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_compare_columns() {
+    let small = process(900, "Nginx")
+        .memory(1024)
+        .cpu(0.5)
+        .ports(&[80])
+        .build();
+    let large = postgres().build();
+
+    // numbers compare as numbers, names ignore case
+    assert_eq!(Column::Pid.compare(&large, &small), Ordering::Less);
+    assert_eq!(Column::Mem.compare(&small, &large), Ordering::Less);
+    assert_eq!(Column::Cpu.compare(&small, &large), Ordering::Less);
+    assert_eq!(Column::Ports.compare(&small, &large), Ordering::Less);
+    assert_eq!(Column::Name.compare(&small, &large), Ordering::Less);
+}
+
+#[test]
+fn test_sort_order_toggle() {
+    let order = SortOrder::toggle(None, Column::Mem);
+    assert_eq!(order.column, Column::Mem);
+    assert!(!order.descending);
+
+    // the same title flips the direction, another one starts ascending
+    let order = SortOrder::toggle(Some(order), Column::Mem);
+    assert!(order.descending);
+    let order = SortOrder::toggle(Some(order), Column::Pid);
+    assert_eq!(order.column, Column::Pid);
+    assert!(!order.descending);
+}
@@ -0,0 +1,134 @@
+// not every test file uses every helper
+#![allow(dead_code)]
+
+use portsage::port::Binding;
+use portsage::process::ProcessInfo;
+
+/// builds a `ProcessInfo` for tests; fields that are not set get plain defaults
+pub struct ProcessBuilder(ProcessInfo);
+
+/// a running process called `name`, started with just its name and owning no ports
+pub fn process(pid: i32, name: &str) -> ProcessBuilder {
+    ProcessBuilder(ProcessInfo {
+        pid,
+        name: name.to_string(),
+        user: "dummy".into(),
+        cmd: vec![name.to_string()],
+        exe: format!("/usr/bin/{name}"),
+        status: "Running".into(),
+        cpu_usage: 0.0,
+        memory: 1024,
+        virtual_memory: 2048,
+        parent_pid: Some(1),
+        start_time: 0,
+        cwd: "/tmp".into(),
+        ports: vec![],
+        bindings: vec![],
+    })
+}
+
+/// a server started from /srv with `--serve`, as the TUI snapshots show it
+pub fn server(pid: i32, name: &str) -> ProcessBuilder {
+    let exe = format!("/usr/bin/{name}");
+    process(pid, name)
+        .cmd(&[&exe, "--serve"])
+        .cwd("/srv")
+        .cpu(0.5)
+        .memory(2048)
+        .virtual_memory(4096)
+        .start_time(1_700_000_000)
+}
+
+/// postgres run by its own user, with every column filled in
+pub fn postgres() -> ProcessBuilder {
+    process(42, "postgres")
+        .user("postgres")
+        .cmd(&["postgres", "-D", "/data"])
+        .cwd("/var/lib/postgresql")
+        .cpu(1.25)
+        .memory(3 * 1024 * 1024)
+        .start_time(1_700_000_000)
+        .ports(&[5432, 5433])
+}
+
+/// a node dev server without a parent
+pub fn node() -> ProcessBuilder {
+    process(1234, "node")
+        .user("dev")
+        .cmd(&["node", "server.js", "--port=3000"])
+        .cwd("/home/dev/web")
+        .cpu(12.5)
+        .memory(50 * 1024 * 1024)
+        .parent(None)
+        .ports(&[3000, 3001])
+}
+
+impl ProcessBuilder {
+    pub fn cmd(mut self, cmd: &[&str]) -> Self {
+        self.0.cmd = cmd.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn user(mut self, user: &str) -> Self {
+        self.0.user = user.into();
+        self
+    }
+
+    pub fn exe(mut self, exe: &str) -> Self {
+        self.0.exe = exe.into();
+        self
+    }
+
+    pub fn cwd(mut self, cwd: &str) -> Self {
+        self.0.cwd = cwd.into();
+        self
+    }
+
+    pub fn cpu(mut self, cpu_usage: f32) -> Self {
+        self.0.cpu_usage = cpu_usage;
+        self
+    }
+
+    pub fn memory(mut self, memory: u64) -> Self {
+        self.0.memory = memory;
+        self
+    }
+
+    pub fn virtual_memory(mut self, virtual_memory: u64) -> Self {
+        self.0.virtual_memory = virtual_memory;
+        self
+    }
+
+    pub fn parent(mut self, parent_pid: Option<i32>) -> Self {
+        self.0.parent_pid = parent_pid;
+        self
+    }
+
+    pub fn start_time(mut self, start_time: u64) -> Self {
+        self.0.start_time = start_time;
+        self
+    }
+
+    /// listening on 127.0.0.1
+    pub fn ports(self, ports: &[u16]) -> Self {
+        ports
+            .iter()
+            .fold(self, |builder, &port| builder.listen("127.0.0.1", port))
+    }
+
+    /// listening on `addr`, which may be a wildcard such as `0.0.0.0` or `*`
+    pub fn listen(mut self, addr: &str, port: u16) -> Self {
+        if !self.0.ports.contains(&port) {
+            self.0.ports.push(port);
+        }
+        self.0.bindings.push(Binding {
+            addr: addr.into(),
+            port,
+        });
+        self
+    }
+
+    pub fn build(self) -> ProcessInfo {
+        self.0
+    }
+}
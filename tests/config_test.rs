@@ -0,0 +1,211 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use portsage::bindings::KeyBindings;
+use portsage::config::{default_config, Config, DEFAULT_REFRESH};
+use portsage::output::OutputFormat;
+use portsage::theme::Theme;
+use ratatui::style::Color;
+use std::time::Duration;
+
+#[test]
+fn test_parse_presets() {
+    let config = Config::parse(
+        r#"
+[presets.db]
+filter = "port:5432"
+key = "1"
+
+[presets.django]
+filter = "python manage.py"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.preset("db").unwrap().filter, "port:5432");
+    assert_eq!(config.preset("django").unwrap().key, None);
+    assert_eq!(config.preset_for_key('1').unwrap().0, "db");
+    assert!(config.preset_for_key('2').is_none());
+}
+
+#[test]
+fn test_reject_unknown_fields() {
+    assert!(Config::parse("[presets.db]\nfliter = \"node\"\n").is_err());
+}
+
+#[test]
+fn test_reject_shadowed_preset_keys() {
+    let err = Config::parse("[presets.db]\nfilter = \"postgres\"\nkey = \"x\"\n").unwrap_err();
+    assert!(err.to_string().contains("already bound to `kill`"), "{err}");
+    let err = Config::parse("[presets.db]\nfilter = \"postgres\"\nkey = \"G\"\n").unwrap_err();
+    assert!(err.to_string().contains("`bottom`"), "{err}");
+    assert!(Config::parse("[presets.db]\nfilter = \"postgres\"\nkey = \"g\"\n").is_err());
+    assert!(Config::parse(
+        "[presets.a]\nfilter = \"a\"\nkey = \"1\"\n[presets.b]\nfilter = \"b\"\nkey = \"1\"\n"
+    )
+    .is_err());
+
+    // free once the binding moves elsewhere
+    let config = Config::parse(
+        "[keys]\nkill = \"ctrl-k\"\n[presets.db]\nfilter = \"postgres\"\nkey = \"x\"\n",
+    )
+    .unwrap();
+    assert_eq!(config.preset_for_key('x').unwrap().0, "db");
+}
+
+#[test]
+fn test_reject_conflicting_key_bindings() {
+    let err = Config::parse("[keys]\nkill = \"q\"\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2: [keys]: `q` is bound to both `quit` and `kill`"
+    );
+    let err = Config::parse("[keys]\nhelp = \"ctrl-p\"\n").unwrap_err();
+    assert!(err.to_string().contains("`up` and `help`"), "{err}");
+
+    // fine once the other action lets go of the key
+    assert!(Config::parse("[keys]\nquit = \"esc\"\nkill = \"q\"\n").is_ok());
+    assert!(Config::parse("[keys]\nquit = [\"q\", \"q\"]\n").is_ok());
+}
+
+#[test]
+fn test_checks_point_at_line() {
+    let line = |text: &str| {
+        let message = Config::parse(text).unwrap_err().to_string();
+        message
+            .strip_prefix("line ")
+            .and_then(|rest| rest.split(':').next())
+            .and_then(|n| n.parse::<usize>().ok())
+            .unwrap_or_else(|| panic!("no line in `{message}`"))
+    };
+
+    // the binding set in the file, whichever of the two actions comes first
+    assert_eq!(
+        line("refresh = \"1s\"\n\n[keys]\nup = \"up\"\nkill = \"q\"\n"),
+        5
+    );
+    assert_eq!(line("[keys]\nhelp = \"?\"\nquit = \"x\"\n"), 3);
+
+    let presets = "\
+[presets.web]
+filter = \"node\"
+key = \"w\"
+
+[presets.db]
+filter = \"postgres\"
+key = \"w\"
+";
+    assert_eq!(line(presets), 3);
+    assert_eq!(line("[defaults]\nlistening = true\npreset = \"nope\"\n"), 3);
+
+    let hooks = "\
+[[hooks]]
+on = \"open\"
+command = \"true\"
+
+[[hooks]]
+on = \"exit\"
+command = \"true\"
+";
+    assert_eq!(line(hooks), 5);
+    assert_eq!(
+        line("hooks = [\n  { on = \"open\", command = \"a\" },\n  { on = \"exit\", command = \"b\" },\n]\n"),
+        3
+    );
+}
+
+#[test]
+fn test_default_config_round_trips() {
+    let config = Config::parse(&default_config()).unwrap();
+
+    assert_eq!(config.keys, KeyBindings::default());
+    assert_eq!(config.theme(None).unwrap(), Theme::default());
+    assert_eq!(config.refresh, Some(DEFAULT_REFRESH));
+    assert_eq!(config.defaults.format, Some(OutputFormat::Table));
+}
+
+#[test]
+fn test_parse_keys_colors_and_defaults() {
+    let config = Config::parse(
+        r##"
+refresh = "500ms"
+
+[defaults]
+listening = true
+preset = "db"
+format = "plain"
+
+[keys]
+kill = "ctrl-k"
+up = ["up", "ctrl-p"]
+
+[colors]
+pid = "magenta"
+selected = "#303030"
+
+[presets.db]
+filter = "postgres"
+"##,
+    )
+    .unwrap();
+
+    assert_eq!(config.refresh, Some(Duration::from_millis(500)));
+    assert!(config.defaults.listening);
+    assert_eq!(config.defaults.preset.as_deref(), Some("db"));
+    assert_eq!(config.defaults.format, Some(OutputFormat::Plain));
+    assert_eq!(
+        config.keys.kill,
+        [KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL)]
+    );
+    assert_eq!(config.keys.up.len(), 2);
+    assert_eq!(config.keys.quit, KeyBindings::default().quit);
+    let theme = config.theme(None).unwrap();
+    assert_eq!(theme.pid, Color::Magenta);
+    assert_eq!(theme.selected, Color::Rgb(0x30, 0x30, 0x30));
+    assert_eq!(theme.ports, Theme::default().ports);
+}
+
+#[test]
+fn test_invalid_values_point_at_line() {
+    let err = Config::parse("[keys]\nquit = \"q\"\nkill = \"hyper-k\"\n").unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("line 3"), "{message}");
+    assert!(message.contains("unknown modifier"), "{message}");
+
+    let err = Config::parse("\n[colors]\npid = \"greeen\"\n").unwrap_err();
+    assert!(err.to_string().contains("line 3"));
+
+    assert!(Config::parse("[keys]\nnuke = \"x\"\n").is_err());
+    assert!(Config::parse("refresh = \"soon\"\n").is_err());
+}
+
+#[test]
+fn test_resolve_themes() {
+    let config = Config::parse(
+        r#"
+theme = "light"
+
+[themes.ocean]
+pid = "cyan"
+
+[colors]
+exposed = "magenta"
+"#,
+    )
+    .unwrap();
+
+    let light = config.theme(None).unwrap();
+    assert_eq!(light.popup_bg, Theme::light().popup_bg);
+    assert_eq!(light.exposed, Color::Magenta);
+
+    let ocean = config.theme(Some("ocean")).unwrap();
+    assert_eq!(ocean.pid, Color::Cyan);
+    assert_eq!(ocean.ports, Theme::default().ports);
+    assert_eq!(ocean.exposed, Color::Magenta);
+
+    assert_eq!(config.theme(Some("monochrome")).unwrap().pid, Color::Reset);
+    assert!(config.theme(Some("neon")).is_err());
+    assert!(Config::parse(
+        "[themes.x]
+pid = \"nope\"\n"
+    )
+    .is_err());
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use common::process;
+use portsage::check::EXIT_ERROR;
+use portsage::diff::{diff_states, EXIT_CHANGED};
+use portsage::snapshot::{HostInfo, Snapshot, SNAPSHOT_VERSION};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[test]
+fn test_diff_states_listeners() {
+    let before = [
+        process(987, "postgres")
+            .start_time(10)
+            .ports(&[5432])
+            .build(),
+        process(1234, "node")
+            .start_time(20)
+            .ports(&[3000, 8080])
+            .build(),
+    ];
+    let after = [
+        process(1234, "node").start_time(20).ports(&[3000]).build(),
+        process(2001, "node").start_time(30).ports(&[8080]).build(),
+        process(2000, "redis").start_time(40).ports(&[6379]).build(),
+    ];
+
+    let diff = diff_states(&before, &after);
+
+    assert_eq!(diff.listeners_added.len(), 1);
+    assert_eq!(diff.listeners_added[0].to_string(), "6379 redis[2000]");
+    assert_eq!(diff.listeners_removed.len(), 1);
+    assert_eq!(diff.listeners_removed[0].to_string(), "5432 postgres[987]");
+    assert_eq!(diff.ports_moved.len(), 1);
+    assert_eq!(diff.ports_moved[0].port, 8080);
+    assert_eq!(diff.ports_moved[0].from[0].pid, 1234);
+    assert_eq!(diff.ports_moved[0].to[0].pid, 2001);
+}
+
+#[test]
+fn test_diff_states_processes() {
+    let before = [
+        process(1, "init").build(),
+        process(50, "sleep").start_time(5).build(),
+    ];
+    // pid 50 was reused by a different process
+    let after = [
+        process(1, "init").build(),
+        process(50, "cat").start_time(9).build(),
+        process(60, "bash").start_time(9).build(),
+    ];
+
+    let diff = diff_states(&before, &after);
+    let started: Vec<i32> = diff.processes_started.iter().map(|p| p.pid).collect();
+    let exited: Vec<String> = diff
+        .processes_exited
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+    assert_eq!(started, [50, 60]);
+    assert_eq!(exited, ["sleep[50]"]);
+    assert!(!diff.listeners_changed());
+    assert!(diff.listeners_only().is_empty());
+}
+
+#[test]
+fn test_diff_states_display() {
+    let same = [process(1, "init").ports(&[22]).build()];
+    assert_eq!(diff_states(&same, &same).to_string(), "No differences\n");
+
+    let after = [
+        process(1, "init").build(),
+        process(7, "nc").start_time(3).ports(&[9000]).build(),
+    ];
+    assert_eq!(
+        diff_states(&same, &after).to_string(),
+        "Listeners added (1):\n  + 9000 nc[7]\n\
+         Listeners removed (1):\n  - 22 init[1]\n\
+         Processes started (1):\n  + nc[7]\n"
+    );
+}
+
+#[test]
+fn test_diff_states_json() {
+    let before = [process(1, "init").build()];
+    let after = [process(1, "init").ports(&[80]).build()];
+    let json = serde_json::to_value(diff_states(&before, &after)).unwrap();
+
+    assert_eq!(json["listeners_added"][0]["port"], 80);
+    assert!(json["processes_started"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_listeners_only_keeps_processes_with_ports() {
+    let before = [
+        process(1, "init").build(),
+        process(5, "sshd").start_time(1).ports(&[22]).build(),
+    ];
+    let after = [
+        process(1, "init").build(),
+        process(8, "sleep").start_time(2).build(),
+        process(9, "nginx").start_time(2).ports(&[80]).build(),
+    ];
+
+    let diff = diff_states(&before, &after).listeners_only();
+    let started: Vec<i32> = diff.processes_started.iter().map(|p| p.pid).collect();
+    let exited: Vec<i32> = diff.processes_exited.iter().map(|p| p.pid).collect();
+    assert_eq!(started, [9]);
+    assert_eq!(exited, [5]);
+}
+
+#[test]
+fn test_second_holder_is_added_not_moved() {
+    // SO_REUSEPORT: a second process joins one that keeps the port
+    let before = [process(10, "nginx").ports(&[80]).build()];
+    let after = [
+        process(10, "nginx").ports(&[80]).build(),
+        process(11, "nginx").ports(&[80]).build(),
+    ];
+
+    let diff = diff_states(&before, &after);
+    assert!(diff.ports_moved.is_empty());
+    assert_eq!(diff.listeners_added[0].to_string(), "80 nginx[11]");
+
+    let diff = diff_states(&after, &before);
+    assert!(diff.ports_moved.is_empty());
+    assert_eq!(diff.listeners_removed[0].to_string(), "80 nginx[11]");
+}
+
+/// run `portsage diff --exit-code` on two files and return its exit code
+fn diff_exit_code(before: &Path, after: &Path) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_portsage"))
+        .args(["diff", "--exit-code"])
+        .args([before, after])
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+fn save_snapshot(name: &str, ports: &[u16]) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("portsage-diff-{name}-{}.json", std::process::id()));
+    Snapshot {
+        version: SNAPSHOT_VERSION,
+        timestamp: 1_700_000_000,
+        host: HostInfo::default(),
+        processes: vec![process(10, "nginx").ports(ports).build()],
+    }
+    .save(&path)
+    .unwrap();
+    path
+}
+
+#[test]
+fn test_exit_code_tells_changes_from_errors() {
+    let before = save_snapshot("before", &[80]);
+    let after = save_snapshot("after", &[80, 443]);
+    let broken =
+        std::env::temp_dir().join(format!("portsage-diff-broken-{}.json", std::process::id()));
+    std::fs::write(&broken, "{ not json").unwrap();
+    let missing = std::env::temp_dir().join("portsage-diff-does-not-exist.json");
+
+    assert_eq!(diff_exit_code(&before, &before), Some(0));
+    assert_eq!(diff_exit_code(&before, &after), Some(EXIT_CHANGED));
+    assert_eq!(diff_exit_code(&before, &broken), Some(EXIT_ERROR));
+    assert_eq!(diff_exit_code(&missing, &after), Some(EXIT_ERROR));
+
+    for path in [before, after, broken] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,28 +1,14 @@
-use portsage::filter::filter_processes_by_name;
-use portsage::process::ProcessInfo;
-
-fn mock_process(pid: i32, name: &str, cmd: &[&str]) -> ProcessInfo {
-    ProcessInfo {
-        pid,
-        name: name.to_string(),
-        cmd: cmd.iter().map(|s| s.to_string()).collect(),
-        exe: "/usr/bin/dummy".into(),
-        status: "Running".into(),
-        cpu_usage: 0.0,
-        memory: 1024,
-        virtual_memory: 2048,
-        parent_pid: Some(1),
-        start_time: 0,
-        cwd: "/tmp".into(),
-    }
-}
+mod common;
+
+use common::process;
+use portsage::filter::{filter_processes, filter_processes_by_name, glob_match};
 
 #[test]
 fn test_filter_by_name() {
     let processes = vec![
-        mock_process(1, "uvicorn", &["uvicorn", "main:app"]),
-        mock_process(2, "node", &["node", "index.js"]),
-        mock_process(3, "python3", &["python3", "server.py"]),
+        process(1, "uvicorn").cmd(&["uvicorn", "main:app"]).build(),
+        process(2, "node").cmd(&["node", "index.js"]).build(),
+        process(3, "python3").cmd(&["python3", "server.py"]).build(),
     ];
 
     let filtered = filter_processes_by_name(&processes, "uvicorn");
@@ -36,3 +22,39 @@ fn test_filter_by_name() {
     let filtered3 = filter_processes_by_name(&processes, "notfound");
     assert_eq!(filtered3.len(), 0);
 }
+
+#[test]
+fn test_filter_by_query() {
+    let mut db = process(10, "postgres")
+        .cmd(&["postgres", "-D", "/var/lib/pg"])
+        .build();
+    db.ports = vec![5432];
+    let processes = vec![
+        db,
+        process(20, "python3")
+            .cmd(&["python3", "manage.py", "runserver"])
+            .build(),
+    ];
+
+    let by_port = filter_processes(&processes, "port:5432");
+    assert_eq!(by_port.len(), 1);
+    assert_eq!(by_port[0].pid, 10);
+
+    let by_cmd = filter_processes(&processes, "python3 manage.py");
+    assert_eq!(by_cmd.len(), 1);
+    assert_eq!(by_cmd[0].pid, 20);
+
+    assert!(filter_processes(&processes, "port:8080").is_empty());
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("postgres", "postgres"));
+    assert!(glob_match("post*", "postgres"));
+    assert!(glob_match("*gres", "postgres"));
+    assert!(glob_match("p*g*s", "postgres"));
+    assert!(glob_match("node?", "node2"));
+    assert!(glob_match("*", ""));
+    assert!(!glob_match("node?", "node"));
+    assert!(!glob_match("post*x", "postgres"));
+}
@@ -0,0 +1,21 @@
+use portsage::format::{format_bytes, format_timestamp, join_ports};
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00");
+    assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+}
+
+#[test]
+fn test_join_ports() {
+    assert_eq!(join_ports(&[]), "");
+    assert_eq!(join_ports(&[80, 443]), "80, 443");
+}
@@ -0,0 +1,57 @@
+use portsage::freeport::{find_free_ports, PortRange};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, TcpListener};
+
+#[test]
+fn test_parse_port_range() {
+    let range: PortRange = "3000-3999".parse().unwrap();
+    assert_eq!(
+        range,
+        PortRange {
+            start: 3000,
+            end: 3999
+        }
+    );
+
+    let single: PortRange = "8080".parse().unwrap();
+    assert_eq!(
+        single,
+        PortRange {
+            start: 8080,
+            end: 8080
+        }
+    );
+
+    assert!("4000-3000".parse::<PortRange>().is_err());
+    assert!("0-10".parse::<PortRange>().is_err());
+    assert!("abc".parse::<PortRange>().is_err());
+}
+
+#[test]
+fn test_skip_ports_in_use() {
+    let port_map = HashMap::from([(3000, 1234), (3002, 5678)]);
+    let range = PortRange {
+        start: 3000,
+        end: 3010,
+    };
+
+    let ports = find_free_ports(range, 3, &port_map, None);
+    assert_eq!(ports, vec![3001, 3003, 3004]);
+}
+
+#[test]
+fn test_verify_by_binding() {
+    let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let listener = TcpListener::bind((localhost, 0)).unwrap();
+    let taken = listener.local_addr().unwrap().port();
+    let range = PortRange {
+        start: taken,
+        end: taken,
+    };
+
+    assert_eq!(
+        find_free_ports(range, 1, &HashMap::new(), None),
+        vec![taken]
+    );
+    assert!(find_free_ports(range, 1, &HashMap::new(), Some(localhost)).is_empty());
+}
@@ -0,0 +1,43 @@
+use portsage::history::FilterHistory;
+
+#[test]
+fn test_navigate_history() {
+    let mut history = FilterHistory::new(vec!["node".into(), "port:5432".into()]);
+
+    assert_eq!(history.older("pyth"), Some("port:5432"));
+    assert_eq!(history.older("pyth"), Some("node"));
+    assert_eq!(history.older("pyth"), Some("node"));
+    assert_eq!(history.newer(), Some("port:5432"));
+    assert_eq!(history.newer(), Some("pyth"));
+    assert_eq!(history.newer(), None);
+}
+
+#[test]
+fn test_push_deduplicates() {
+    let mut history = FilterHistory::new(vec!["node".into(), "python".into()]);
+    history.push("node");
+    history.push("  ");
+
+    assert_eq!(
+        history.entries(),
+        &["python".to_string(), "node".to_string()]
+    );
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = std::env::temp_dir().join(format!("portsage-history-{}", std::process::id()));
+
+    let mut history = FilterHistory::load_from(&path);
+    history.push("python manage.py");
+    history.push("port:5432");
+    history.save().unwrap();
+
+    let loaded = FilterHistory::load_from(&path);
+    assert_eq!(
+        loaded.entries(),
+        &["python manage.py".to_string(), "port:5432".to_string()]
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
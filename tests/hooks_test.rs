@@ -0,0 +1,83 @@
+use portsage::config::Config;
+use portsage::hooks::{HookEvent, HookTrigger};
+
+fn event(trigger: HookTrigger, ports: Vec<u16>, name: &str) -> HookEvent {
+    HookEvent {
+        trigger,
+        ports,
+        pid: 1234,
+        name: name.into(),
+        cwd: "/srv/web".into(),
+    }
+}
+
+#[test]
+fn test_parse_and_match_hooks() {
+    let config = Config::parse(
+        r#"
+[[hooks]]
+on = "open"
+ports = [3000]
+command = "xdg-open http://localhost:$PORTSAGE_PORT"
+
+[[hooks]]
+on = "exit"
+process = "ssh*"
+command = "notify-send 'tunnel dropped'"
+"#,
+    )
+    .unwrap();
+    let [open, exit] = config.hooks.as_slice() else {
+        panic!("expected two hooks");
+    };
+
+    assert!(open.matches(&event(HookTrigger::Open, vec![3000], "node")));
+    assert!(!open.matches(&event(HookTrigger::Open, vec![8080], "node")));
+    assert!(!open.matches(&event(HookTrigger::Close, vec![3000], "node")));
+
+    assert!(exit.matches(&event(HookTrigger::Exit, vec![], "ssh")));
+    assert!(!exit.matches(&event(HookTrigger::Exit, vec![], "node")));
+}
+
+#[test]
+fn test_reject_unknown_trigger() {
+    assert!(Config::parse("[[hooks]]\non = \"start\"\ncommand = \"true\"\n").is_err());
+}
+
+#[test]
+fn test_exit_hooks_need_a_filter() {
+    let err = Config::parse("[[hooks]]\non = \"exit\"\ncommand = \"true\"\n").unwrap_err();
+    assert!(
+        err.to_string().contains("needs `process` or `ports`"),
+        "{err}"
+    );
+    assert!(Config::parse("[[hooks]]\non = \"exit\"\nports = [22]\ncommand = \"true\"\n").is_ok());
+    // any port opening is a narrow enough event
+    assert!(Config::parse("[[hooks]]\non = \"open\"\ncommand = \"true\"\n").is_ok());
+}
+
+#[test]
+fn test_hook_env() {
+    let env = event(HookTrigger::Exit, vec![8080, 8443], "ssh").env();
+    let get = |key: &str| env.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+
+    assert_eq!(get("PORTSAGE_EVENT"), "exit");
+    assert_eq!(get("PORTSAGE_PORT"), "8080");
+    assert_eq!(get("PORTSAGE_PORTS"), "8080,8443");
+    assert_eq!(get("PORTSAGE_PID"), "1234");
+    assert_eq!(get("PORTSAGE_NAME"), "ssh");
+}
+
+#[test]
+fn test_spawn_passes_env() {
+    let config = Config::parse(
+        "[[hooks]]\non = \"open\"\ncommand = \"test \\\"$PORTSAGE_PORT\\\" = 3000\"\n",
+    )
+    .unwrap();
+    let status = config.hooks[0]
+        .spawn(&event(HookTrigger::Open, vec![3000], "node"))
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,64 @@
+use portsage::output::{render_rows, OutputFormat};
+
+fn sample() -> (Vec<String>, Vec<Vec<String>>) {
+    let headers = ["pid", "name", "ports", "command"]
+        .map(String::from)
+        .to_vec();
+    let rows = vec![
+        [
+            "1234",
+            "node",
+            "3000, 3001",
+            "node server.js --title \"a, b\"",
+        ]
+        .map(String::from)
+        .to_vec(),
+        ["5", "Web Content", "", "firefox -contentproc"]
+            .map(String::from)
+            .to_vec(),
+    ];
+    (headers, rows)
+}
+
+#[test]
+fn test_render_csv() {
+    let (headers, rows) = sample();
+    assert_eq!(
+        render_rows(&headers, &rows, OutputFormat::Csv, true),
+        "pid,name,ports,command\n\
+         1234,node,\"3000, 3001\",\"node server.js --title \"\"a, b\"\"\"\n\
+         5,Web Content,,firefox -contentproc"
+    );
+}
+
+#[test]
+fn test_render_tsv_without_header() {
+    let (headers, rows) = sample();
+    assert_eq!(
+        render_rows(&headers, &rows[1..], OutputFormat::Tsv, false),
+        "5\tWeb Content\t\tfirefox -contentproc"
+    );
+}
+
+#[test]
+fn test_render_markdown() {
+    let headers = vec!["pid".to_string(), "command".to_string()];
+    let rows = vec![vec!["7".to_string(), "grep a|b".to_string()]];
+    assert_eq!(
+        render_rows(&headers, &rows, OutputFormat::Markdown, true),
+        "| pid | command |\n| --- | --- |\n| 7 | grep a\\|b |"
+    );
+}
+
+#[test]
+fn test_render_plain_is_awk_friendly() {
+    let (headers, rows) = sample();
+    let plain = render_rows(&headers, &rows, OutputFormat::Plain, true);
+    assert_eq!(
+        plain,
+        "pid   name         ports      command\n\
+         1234  node         3000,3001  node server.js --title \"a, b\"\n\
+         5     Web_Content  -          firefox -contentproc"
+    );
+    assert!(plain.lines().all(|l| l.split_whitespace().count() >= 4));
+}
@@ -1,4 +1,4 @@
-use portsage::port::parse_lsof_output;
+use portsage::port::{parse_lsof_bindings, parse_lsof_output, Binding};
 
 #[test]
 fn test_parse_lsof_output() {
@@ -16,3 +16,48 @@ docker-pr  9012 user   22u  IPv6 0x34567890      0t0  TCP *:5432 (LISTEN)
     assert_eq!(map.get(&5432), Some(&9012));
     assert_eq!(map.get(&9999), None);
 }
+
+#[test]
+fn test_parse_lsof_bindings() {
+    let mock_output = r#"
+COMMAND     PID USER   FD   TYPE             DEVICE SIZE/OFF NODE NAME
+uvicorn    1234 user   10u  IPv4 0x12345678      0t0  TCP *:8000 (LISTEN)
+postgres    987 user    5u  IPv4 0x23456789      0t0  TCP 127.0.0.1:5432 (LISTEN)
+postgres    987 user    6u  IPv6 0x34567890      0t0  TCP [::1]:5432 (LISTEN)
+"#;
+
+    let bindings = parse_lsof_bindings(mock_output);
+    let shown: Vec<String> = bindings
+        .iter()
+        .map(|(pid, b)| format!("{pid} {b}"))
+        .collect();
+
+    assert_eq!(
+        shown,
+        ["1234 *:8000", "987 127.0.0.1:5432", "987 [::1]:5432"]
+    );
+    assert!(bindings[0].1.is_exposed());
+    assert!(bindings[1].1.is_loopback() && bindings[2].1.is_loopback());
+    assert!(!bindings[2].1.is_exposed());
+}
+
+#[test]
+fn test_binding_exposure() {
+    let binding = |addr: &str| Binding {
+        addr: addr.into(),
+        port: 5432,
+    };
+    for addr in ["*", "0.0.0.0", "::"] {
+        assert!(binding(addr).is_exposed(), "{addr}");
+        assert!(!binding(addr).is_loopback(), "{addr}");
+    }
+    for addr in ["127.0.0.1", "::1", "localhost"] {
+        assert!(!binding(addr).is_exposed(), "{addr}");
+        assert!(binding(addr).is_loopback(), "{addr}");
+    }
+    // a LAN address is reachable from that network but is neither
+    for addr in ["192.168.1.20", "fe80::1"] {
+        assert!(!binding(addr).is_exposed(), "{addr}");
+        assert!(!binding(addr).is_loopback(), "{addr}");
+    }
+}
@@ -1,33 +1,27 @@
-use portsage::process::{get_all_processes, ProcessInfo};
+mod common;
+
+use common::process;
+use portsage::process::get_all_processes;
 
 #[test]
 fn test_process_list_is_not_empty() {
-    let processes = get_all_processes();
+    let processes = get_all_processes().unwrap();
     assert!(!processes.is_empty(), "プロセス一覧が空です");
 }
 
 #[test]
 fn test_filter_sample() {
-    let mock_proc = ProcessInfo {
-        pid: 1001,
-        name: "dummy-process".into(),
-        cmd: vec!["dummy".into(), "--test".into()],
-        exe: "/usr/bin/dummy".into(),
-        status: "Running".into(),
-        cpu_usage: 1.0,
-        memory: 2048,
-        virtual_memory: 4096,
-        parent_pid: Some(1),
-        start_time: 0,
-        cwd: "/home/dummy".into(),
-    };
+    let mock_proc = process(1001, "dummy-process")
+        .cmd(&["dummy", "--test"])
+        .cwd("/home/dummy")
+        .build();
 
     assert!(mock_proc.name.contains("dummy"));
 }
 
 #[test]
 fn test_contains_shell_or_cargo() {
-    let processes = get_all_processes();
+    let processes = get_all_processes().unwrap();
     let has_expected = processes.iter().any(|proc| {
         let name = proc.name.to_lowercase();
         name.contains("bash")
@@ -43,7 +37,7 @@ fn test_contains_shell_or_cargo() {
 
 #[test]
 fn test_process_fields_are_valid() {
-    let processes = get_all_processes();
+    let processes = get_all_processes().unwrap();
     for proc in processes {
         assert!(proc.pid > 0, "無効な PID: {}", proc.pid);
         assert!(
@@ -67,3 +61,24 @@ fn test_process_fields_are_valid() {
         );
     }
 }
+
+#[test]
+fn test_process_exposure() {
+    let exposed = process(10, "postgres")
+        .listen("127.0.0.1", 5432)
+        .listen("::", 5432)
+        .build();
+    assert!(exposed.is_exposed());
+    assert!(!exposed.is_loopback_only());
+
+    let local = process(11, "redis")
+        .listen("127.0.0.1", 6379)
+        .listen("::1", 6379)
+        .build();
+    assert!(!local.is_exposed());
+    assert!(local.is_loopback_only());
+
+    let lan = process(12, "vite").listen("192.168.1.20", 5173).build();
+    assert!(!lan.is_exposed() && !lan.is_loopback_only());
+    assert!(!process(13, "sleep").build().is_loopback_only());
+}
@@ -0,0 +1,170 @@
+use portsage::procfs::{
+    is_secret, mask_value, parse_environ, parse_limits, parse_net_sockets, summarize_maps,
+    OpenFile, MASK,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const LIMITS: &str = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 524288               files
+Max nice priority         0                    0
+Max realtime timeout      unlimited            unlimited            us
+";
+
+#[test]
+fn test_parse_environ() {
+    let vars = parse_environ(b"PATH=/usr/bin:/bin\0EMPTY=\0A=b=c\0NOVALUE\0");
+    assert_eq!(
+        vars,
+        vec![
+            ("PATH".to_string(), "/usr/bin:/bin".to_string()),
+            ("EMPTY".to_string(), String::new()),
+            ("A".to_string(), "b=c".to_string()),
+            ("NOVALUE".to_string(), String::new()),
+        ]
+    );
+    assert!(parse_environ(b"").is_empty());
+}
+
+#[test]
+fn test_secret_names() {
+    for name in [
+        "GITHUB_TOKEN",
+        "DB_PASSWORD",
+        "aws_secret_access_key",
+        "API_KEY",
+        "STRIPE_APIKEY",
+        "SSH_AUTH_SOCK",
+        "PGPASSWORD",
+        "REDIS_PASS",
+    ] {
+        assert!(is_secret(name), "{name}");
+    }
+    for name in ["PATH", "HOME", "PWD", "KEYBOARD_LAYOUT", "AUTHOR", "LANG"] {
+        assert!(!is_secret(name), "{name}");
+    }
+}
+
+#[test]
+fn test_mask_value() {
+    assert_eq!(mask_value("GITHUB_TOKEN", "ghp_123"), MASK);
+    assert_eq!(mask_value("GITHUB_TOKEN", ""), "");
+    assert_eq!(mask_value("HOME", "/root"), "/root");
+    assert_eq!(
+        mask_value("DATABASE_URL", "postgres://app:hunter2@db:5432/app"),
+        format!("postgres://app:{MASK}@db:5432/app")
+    );
+    // without a password the userinfo may be a token itself
+    assert_eq!(
+        mask_value("UPSTREAM", "https://ghp_123@github.com/org/repo.git"),
+        format!("https://{MASK}@github.com/org/repo.git")
+    );
+    assert_eq!(
+        mask_value("UPSTREAM", "https://:token@example.com"),
+        format!("https://:{MASK}@example.com")
+    );
+    // an @ that is not in the host part
+    assert_eq!(
+        mask_value("UPSTREAM", "https://example.com/a@b"),
+        "https://example.com/a@b"
+    );
+    assert_eq!(
+        mask_value("UPSTREAM", "https://example.com/a:b@c"),
+        "https://example.com/a:b@c"
+    );
+}
+
+#[test]
+fn test_parse_limits() {
+    let limits = parse_limits(LIMITS);
+    assert_eq!(limits.len(), 4);
+    assert_eq!(limits[1].name, "Max open files");
+    assert_eq!(limits[1].soft, "1024");
+    assert_eq!(limits[1].hard, "524288");
+    assert_eq!(limits[1].units, "files");
+
+    // no unit and a line shorter than the header
+    assert_eq!(limits[2].name, "Max nice priority");
+    assert_eq!(limits[2].hard, "0");
+    assert_eq!(limits[2].units, "");
+
+    assert!(parse_limits("").is_empty());
+    assert!(parse_limits("not a limits file\n").is_empty());
+}
+
+#[test]
+fn test_parse_net_sockets() {
+    let tcp = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 4343 1 0000000000000000 20 4 30 10 -1
+";
+    let sockets = parse_net_sockets(tcp, "tcp");
+    assert_eq!(sockets.len(), 2);
+    assert_eq!(sockets[0].local, (IpAddr::V4(Ipv4Addr::LOCALHOST), 8080));
+    assert_eq!(sockets[0].state, "LISTEN");
+    assert_eq!(sockets[0].inode, 4242);
+    assert_eq!(sockets[1].remote.1, 54321);
+    assert_eq!(sockets[1].state, "ESTABLISHED");
+    assert_eq!(
+        sockets[0].to_string(),
+        "tcp   LISTEN      127.0.0.1:8080 -> 0.0.0.0:0"
+    );
+
+    let tcp6 = "\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000001000000:0050 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 77 1 0000000000000000 100 0 0 10 0
+";
+    let sockets = parse_net_sockets(tcp6, "tcp6");
+    assert_eq!(sockets[0].local, (IpAddr::V6(Ipv6Addr::LOCALHOST), 80));
+    assert_eq!(
+        sockets[0].to_string(),
+        "tcp6  LISTEN      [::1]:80 -> [::]:0"
+    );
+
+    let udp = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  100: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 99 2 0000000000000000 0
+";
+    assert_eq!(parse_net_sockets(udp, "udp")[0].state, "UNCONN");
+}
+
+#[test]
+fn test_socket_inode() {
+    let file = |target: &str| OpenFile {
+        fd: 3,
+        target: target.into(),
+    };
+    assert_eq!(file("socket:[4242]").socket_inode(), Some(4242));
+    assert_eq!(file("pipe:[4242]").socket_inode(), None);
+    assert_eq!(file("/tmp/socket:[1]").socket_inode(), None);
+}
+
+#[test]
+fn test_summarize_maps() {
+    let maps = "\
+55df33ade000-55df33ae0000 r--p 00000000 fe:00 317783                     /usr/bin/head
+55df33ae0000-55df33ae6000 r-xp 00002000 fe:00 317783                     /usr/bin/head
+55df34000000-55df34100000 rw-p 00000000 00:00 0                          [heap]
+7f0000000000-7f0000001000 rw-p 00000000 00:00 0
+7f0000002000-7f0000003000 rw-p 00000000 00:00 0
+7f0000004000-7f0000005000 r--p 00000000 fe:00 1234                       /tmp/my file (deleted)
+";
+    let summary = summarize_maps(maps);
+    let names: Vec<&str> = summary.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "[heap]",
+            "/usr/bin/head",
+            "[anonymous]",
+            "/tmp/my file (deleted)"
+        ]
+    );
+    assert_eq!(summary[0].size, 0x100000);
+    assert_eq!(summary[1].size, 0x8000);
+    assert_eq!(summary[1].count, 2);
+    assert_eq!(summary[2].size, 0x2000);
+    assert_eq!(summary[2].count, 2);
+}
@@ -0,0 +1,68 @@
+use portsage::tui::ListPosition;
+
+#[test]
+fn test_move_stops_at_ends() {
+    let mut position = ListPosition::default();
+
+    position.move_by(-1, 10);
+    assert_eq!(position.selected, 0);
+    position.move_by(25, 10);
+    assert_eq!(position.selected, 9);
+    position.move_by(1, 0);
+    assert_eq!(position.selected, 0);
+
+    position.select(usize::MAX, 10);
+    assert_eq!(position.selected, 9);
+}
+
+#[test]
+fn test_scroll_into_view_uses_viewport() {
+    let mut position = ListPosition::default();
+
+    // a 5-row viewport scrolls on the 6th row, not the 21st
+    position.select(5, 100);
+    position.scroll_into_view(5, 100);
+    assert_eq!(position.offset, 1);
+
+    // a taller viewport does not scroll early
+    position.scroll_into_view(40, 100);
+    assert_eq!(position.offset, 1);
+    position.select(30, 100);
+    position.scroll_into_view(40, 100);
+    assert_eq!(position.offset, 1);
+
+    position.select(0, 100);
+    position.scroll_into_view(40, 100);
+    assert_eq!(position.offset, 0);
+}
+
+#[test]
+fn test_scroll_into_view_after_shrinking() {
+    let mut position = ListPosition {
+        selected: 90,
+        offset: 80,
+    };
+
+    // filtering leaves 12 rows: the selection and offset are clamped
+    position.scroll_into_view(10, 12);
+    assert_eq!(
+        position,
+        ListPosition {
+            selected: 11,
+            offset: 2
+        }
+    );
+
+    // a zero-height viewport still keeps the selection on screen
+    position.scroll_into_view(0, 12);
+    assert_eq!(position.offset, 11);
+
+    position.scroll_into_view(10, 0);
+    assert_eq!(
+        position,
+        ListPosition {
+            selected: 0,
+            offset: 0
+        }
+    );
+}
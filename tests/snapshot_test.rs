@@ -0,0 +1,50 @@
+mod common;
+
+use common::process;
+use portsage::snapshot::{HostInfo, Snapshot, SNAPSHOT_VERSION};
+
+#[test]
+fn test_save_and_load_snapshot() {
+    let path = std::env::temp_dir().join(format!("portsage-snapshot-{}.json", std::process::id()));
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        timestamp: 1_700_000_000,
+        host: HostInfo {
+            hostname: "web-01".into(),
+            ..HostInfo::default()
+        },
+        processes: vec![process(42, "nginx").ports(&[80, 443]).build()],
+    };
+
+    snapshot.save(&path).unwrap();
+    let loaded = Snapshot::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.label(), "web-01 @ 2023-11-14 22:13:20 UTC");
+    assert_eq!(loaded.processes.len(), 1);
+    assert_eq!(loaded.processes[0].name, "nginx");
+    assert_eq!(loaded.processes[0].ports, vec![80, 443]);
+}
+
+#[test]
+fn test_reject_newer_snapshot_version() {
+    let path =
+        std::env::temp_dir().join(format!("portsage-snapshot-new-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"{"version": 99, "timestamp": 0, "host": {}, "processes": []}"#,
+    )
+    .unwrap();
+
+    let result = Snapshot::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_capture_records_host() {
+    let snapshot = Snapshot::capture(vec![process(1, "init").build()]);
+    assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+    assert!(snapshot.timestamp > 0);
+    assert_eq!(snapshot.processes.len(), 1);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{node, process};
+use portsage::template::Template;
+
+fn render(source: &str) -> String {
+    Template::parse(source).unwrap().render(&node().build())
+}
+
+#[test]
+fn test_render_fields() {
+    assert_eq!(
+        render(r"{pid}\t{name}\t{ports}\t{cwd}"),
+        "1234\tnode\t3000,3001\t/home/dev/web"
+    );
+    assert_eq!(
+        render("{ppid}|{user}|{cmd}"),
+        "|dev|node server.js --port=3000"
+    );
+}
+
+#[test]
+fn test_render_bindings() {
+    assert_eq!(
+        render("{bindings}"),
+        "127.0.0.1:3000/tcp,127.0.0.1:3001/tcp"
+    );
+
+    let p = process(7, "api")
+        .listen("*", 80)
+        .listen("::1", 8080)
+        .build();
+    let template = Template::parse("{bindings|join: }").unwrap();
+    assert_eq!(template.render(&p), "*:80/tcp [::1]:8080/tcp");
+}
+
+#[test]
+fn test_render_helpers() {
+    assert_eq!(render("{ports|join: }"), "3000 3001");
+    assert_eq!(render("{cmd|join:,}"), "node,server.js,--port=3000");
+    assert_eq!(render("{cmd|truncate:8}"), "node se…");
+    assert_eq!(render("{name|truncate:1}"), "…");
+    assert_eq!(render("[{name|truncate:0}]"), "[]");
+    assert_eq!(render("{name|truncate:4}"), "node");
+    assert_eq!(render("{memory|bytes}"), "50.0 MiB");
+    assert_eq!(render("{start_time|time}"), "1970-01-01 00:00:00");
+    assert_eq!(render("[{name|pad:6}]"), "[node  ]");
+}
+
+#[test]
+fn test_literal_braces() {
+    assert_eq!(render("{{\"pid\": {pid}}}"), "{\"pid\": 1234}");
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(Template::parse("{nope}").is_err());
+    assert!(Template::parse("{pid|shout}").is_err());
+    assert!(Template::parse("{pid|truncate}").is_err());
+    assert!(Template::parse("{pid").is_err());
+    assert!(Template::parse("pid}").is_err());
+}
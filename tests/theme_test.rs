@@ -0,0 +1,36 @@
+use portsage::theme::{paint, ColorChoice, Theme, BUILTIN_THEMES};
+use ratatui::style::{Color, Modifier};
+
+#[test]
+fn test_builtin_themes() {
+    for name in BUILTIN_THEMES {
+        assert!(Theme::builtin(name).is_some(), "{name}");
+    }
+    assert_eq!(Theme::builtin("dark"), Some(Theme::default()));
+    assert!(Theme::builtin("solarized").is_none());
+}
+
+#[test]
+fn test_monochrome_selection_is_reversed() {
+    let style = Theme::monochrome().selected_style();
+    assert!(style.add_modifier.contains(Modifier::REVERSED));
+    assert_eq!(style.bg, None);
+
+    assert_eq!(Theme::default().selected_style().bg, Some(Color::DarkGray));
+}
+
+#[test]
+fn test_color_choice() {
+    assert!(ColorChoice::Always.enabled(false));
+    assert!(!ColorChoice::Never.enabled(true));
+    assert!(!ColorChoice::Auto.enabled(false));
+}
+
+#[test]
+fn test_paint() {
+    assert_eq!(paint("+ 8080", Color::Green, false), "+ 8080");
+    assert_eq!(paint("+ 8080", Color::Reset, true), "+ 8080");
+
+    let painted = paint("+ 8080", Color::Green, true);
+    assert!(painted.starts_with('\u{1b}') && painted.contains("+ 8080"));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::process;
+use portsage::process::ProcessInfo;
+use portsage::tree::render_tree;
+
+fn processes() -> Vec<ProcessInfo> {
+    vec![
+        process(1, "init").parent(None).build(),
+        process(10, "sshd").ports(&[22]).build(),
+        process(20, "bash").build(),
+        process(21, "node")
+            .parent(Some(20))
+            .ports(&[3000, 3001])
+            .build(),
+        process(22, "vim").parent(Some(20)).build(),
+    ]
+}
+
+#[test]
+fn test_render_full_tree() {
+    let tree = render_tree(&processes(), |_| true);
+    assert_eq!(
+        tree,
+        "1 init\n\
+         ├─ 10 sshd [22]\n\
+         └─ 20 bash\n   \
+            ├─ 21 node [3000, 3001]\n   \
+            └─ 22 vim\n"
+    );
+}
+
+#[test]
+fn test_render_keeps_ancestors_of_matches() {
+    let tree = render_tree(&processes(), |p| p.name == "node");
+    assert_eq!(tree, "1 init\n└─ 20 bash\n   └─ 21 node [3000, 3001]\n");
+}
@@ -0,0 +1,673 @@
+mod common;
+
+use common::{process, server};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use portsage::columns::Column;
+use portsage::config::Config;
+use portsage::history::FilterHistory;
+use portsage::process::ProcessInfo;
+use portsage::procfs::{parse_limits, OpenFile, ProcDetails};
+use portsage::theme::Theme;
+use portsage::tui::{copy_text, render, Action, App, CopyTarget, Mode, TuiOptions};
+use ratatui::backend::TestBackend;
+use ratatui::layout::Rect;
+use ratatui::Terminal;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 16;
+
+fn mock_processes() -> Vec<ProcessInfo> {
+    vec![
+        server(100, "postgres").ports(&[5432]).build(),
+        server(200, "node").ports(&[3000, 3001]).build(),
+        server(300, "redis-server").ports(&[6379]).build(),
+        server(400, "bash").build(),
+    ]
+}
+
+fn app(processes: Vec<ProcessInfo>) -> App {
+    let options = TuiOptions {
+        columns: Column::DEFAULT.to_vec(),
+        theme: Theme::monochrome(),
+        ..TuiOptions::default()
+    };
+    App::new(processes, options, FilterHistory::new(vec![]))
+}
+
+fn press(app: &mut App, code: KeyCode) -> Action {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+/// render `app` into a test terminal and return the screen as text
+fn screen(app: &mut App) -> String {
+    sized_screen(app, WIDTH, HEIGHT)
+}
+
+fn sized_screen(app: &mut App, width: u16, height: u16) -> String {
+    app.resize(Rect::new(0, 0, width, height));
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| render(f, app)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let mut text = String::new();
+    for y in 0..height {
+        let line: String = (0..width).map(|x| buffer.get(x, y).symbol()).collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// compare against `tests/snapshots/<name>.txt`; `UPDATE_SNAPSHOTS=1` rewrites it
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}, run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    assert_eq!(actual, expected, "screen differs from {}", path.display());
+}
+
+#[test]
+fn test_normal_screen() {
+    let mut app = app(mock_processes());
+    assert_eq!(app.mode(), Mode::Normal);
+    assert_snapshot("normal", &screen(&mut app));
+}
+
+#[test]
+fn test_filtering_screen() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "redis");
+    assert_eq!(app.mode(), Mode::FilterInput);
+    assert_eq!(app.shown().len(), 1);
+    assert_snapshot("filtering", &screen(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.mode(), Mode::Normal);
+    assert_eq!(app.selected().map(|p| p.pid), Some(300));
+}
+
+#[test]
+fn test_scrolling_screen() {
+    let processes = (1..=30)
+        .map(|i| {
+            server(i, &format!("worker-{i}"))
+                .ports(&[8000 + i as u16])
+                .build()
+        })
+        .collect();
+    let mut app = app(processes);
+    screen(&mut app);
+
+    // the table shows 7 rows at this height, so paging moves by 7
+    press(&mut app, KeyCode::PageDown);
+    press(&mut app, KeyCode::PageDown);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.selected().map(|p| p.pid), Some(16));
+    assert_snapshot("scrolling", &screen(&mut app));
+
+    press(&mut app, KeyCode::End);
+    assert_eq!(app.selected().map(|p| p.pid), Some(30));
+    assert_snapshot("scrolling_end", &screen(&mut app));
+}
+
+#[test]
+fn test_kill_confirmation_screen() {
+    let mut app = app(mock_processes());
+    screen(&mut app);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(app.mode(), Mode::ConfirmKill);
+    assert_snapshot("kill_confirmation", &screen(&mut app));
+
+    assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Kill(200));
+    assert_eq!(app.mode(), Mode::Normal);
+
+    // anything but `y` cancels
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(press(&mut app, KeyCode::Char('n')), Action::None);
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+fn mock_details() -> ProcDetails {
+    ProcDetails {
+        environ: Ok(vec![
+            ("PATH".into(), "/usr/bin:/bin".into()),
+            ("GITHUB_TOKEN".into(), "ghp_abc123".into()),
+            (
+                "DATABASE_URL".into(),
+                "postgres://app:hunter2@db/app".into(),
+            ),
+        ]),
+        open_files: Ok(vec![
+            OpenFile {
+                fd: 0,
+                target: "/dev/null".into(),
+            },
+            OpenFile {
+                fd: 3,
+                target: "socket:[4242]".into(),
+            },
+        ]),
+        limits: Ok(parse_limits(
+            "Limit                     Soft Limit           Hard Limit           Units     \n\
+             Max open files            1024                 524288               files     \n",
+        )),
+        sockets: Ok(vec![]),
+        maps: Err("Permission denied (os error 13)".into()),
+    }
+}
+
+#[test]
+fn test_detail_screen() {
+    let mut app = app(mock_processes());
+    screen(&mut app);
+    assert_eq!(press(&mut app, KeyCode::Tab), Action::LoadDetails(100));
+    assert_eq!(app.mode(), Mode::Detail);
+    assert_snapshot("detail", &screen(&mut app));
+
+    // other tabs wait for the details
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.detail_lines(), vec!["Loading...".to_string()]);
+    app.set_details(100, mock_details());
+    assert_snapshot("detail_environment", &screen(&mut app));
+
+    assert_eq!(
+        press(&mut app, KeyCode::Enter),
+        Action::Copy {
+            text: "DATABASE_URL=postgres://app:********@db/app\n\
+                   GITHUB_TOKEN=********\n\
+                   PATH=/usr/bin:/bin"
+                .into(),
+            label: "Environment tab".into()
+        }
+    );
+
+    press(&mut app, KeyCode::Char('4'));
+    assert_snapshot("detail_limits", &screen(&mut app));
+    press(&mut app, KeyCode::Right);
+    assert_eq!(
+        app.detail_lines(),
+        vec![
+            "No TCP or UDP sockets",
+            "1 other sockets (unix, netlink, ...)"
+        ]
+    );
+    press(&mut app, KeyCode::Right);
+    assert_eq!(
+        app.detail_lines(),
+        vec!["Cannot read /proc/100/maps: Permission denied (os error 13)"]
+    );
+
+    // wrapping around to the overview
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.detail_lines()[0], "PID: 100");
+
+    // a socket table that cannot be read is not blamed on the descriptors
+    app.set_details(
+        100,
+        ProcDetails {
+            sockets: Err("No such file or directory (os error 2)".into()),
+            ..mock_details()
+        },
+    );
+    press(&mut app, KeyCode::Char('5'));
+    assert_eq!(
+        app.detail_lines(),
+        vec!["Cannot read /proc/100/net: No such file or directory (os error 2)"]
+    );
+    press(&mut app, KeyCode::Char('1'));
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_detail_footer_follows_bindings() {
+    let options = TuiOptions {
+        columns: Column::DEFAULT.to_vec(),
+        config: Config::parse("[keys]\ncopy = \"y\"\nup = \"ctrl-p\"\n").unwrap(),
+        ..TuiOptions::default()
+    };
+    let mut app = App::new(mock_processes(), options, FilterHistory::new(vec![]));
+    press(&mut app, KeyCode::Tab);
+    assert!(screen(&mut app).contains("←/→: tab, ctrl-p/down: scroll, y: copy"));
+}
+
+#[test]
+fn test_detail_scrolling() {
+    let mut app = app(mock_processes());
+    screen(&mut app);
+    press(&mut app, KeyCode::Tab);
+    let environ = (0..40)
+        .map(|i| (format!("VAR_{i:02}"), i.to_string()))
+        .collect();
+    app.set_details(
+        100,
+        ProcDetails {
+            environ: Ok(environ),
+            ..mock_details()
+        },
+    );
+    press(&mut app, KeyCode::Char('2'));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    assert_snapshot("detail_scrolled", &screen(&mut app));
+
+    // scrolling stops once the last line is visible
+    press(&mut app, KeyCode::End);
+    let text = screen(&mut app);
+    assert!(text.contains("VAR_39=39"));
+    press(&mut app, KeyCode::Up);
+    assert!(screen(&mut app).contains("VAR_38=38"));
+}
+
+#[test]
+fn test_snapshot_details_are_not_loaded() {
+    let options = TuiOptions {
+        columns: Column::DEFAULT.to_vec(),
+        snapshot: Some("before.json".into()),
+        ..TuiOptions::default()
+    };
+    let mut app = App::new(mock_processes(), options, FilterHistory::new(vec![]));
+    assert_eq!(press(&mut app, KeyCode::Tab), Action::None);
+    press(&mut app, KeyCode::Char('2'));
+    assert_eq!(
+        app.detail_lines(),
+        vec!["Not available for processes in a snapshot"]
+    );
+}
+
+#[test]
+fn test_columns_screen() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Char('c'));
+    assert_eq!(app.mode(), Mode::Columns);
+    assert_snapshot("columns", &screen(&mut app));
+
+    // hide the PID column and save
+    press(&mut app, KeyCode::Char(' '));
+    let action = press(&mut app, KeyCode::Enter);
+    assert_eq!(
+        action,
+        Action::SaveColumns(vec![Column::Name, Column::Ports, Column::Cmd])
+    );
+}
+
+#[test]
+fn test_column_chooser_follows_quit_binding() {
+    let options = TuiOptions {
+        columns: Column::DEFAULT.to_vec(),
+        config: Config::parse("[keys]\nquit = \"ctrl-q\"\nup = [\"up\", \"q\"]\n").unwrap(),
+        ..TuiOptions::default()
+    };
+    let mut app = App::new(mock_processes(), options, FilterHistory::new(vec![]));
+    press(&mut app, KeyCode::Char('c'));
+    assert!(screen(&mut app).contains("ctrl-q: cancel"));
+
+    // `q` is free for the binding that took it, esc is no quit key any more
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char('q'));
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.mode(), Mode::Columns);
+    press(&mut app, KeyCode::Char(' '));
+    assert!(screen(&mut app).contains("[ ] PID"));
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL));
+    assert_eq!(app.mode(), Mode::Normal);
+    // the columns key closes it as well
+    press(&mut app, KeyCode::Char('c'));
+    press(&mut app, KeyCode::Char('c'));
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_help_screen() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.mode(), Mode::Help);
+    assert_snapshot("help", &screen(&mut app));
+}
+
+#[test]
+fn test_key_actions() {
+    let mut app = app(mock_processes());
+    assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+    assert_eq!(
+        press(&mut app, KeyCode::Enter),
+        Action::Copy {
+            text: "100".into(),
+            label: "PID".into()
+        }
+    );
+    assert_eq!(press(&mut app, KeyCode::Char('r')), Action::Reload);
+    assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+}
+
+#[test]
+fn test_ctrl_c_and_ctrl_z() {
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    let mut app = app(mock_processes());
+    assert_eq!(app.handle_key(ctrl('c')), Action::Quit);
+    assert_eq!(app.handle_key(ctrl('z')), Action::Suspend);
+
+    // also while typing a filter or in a popup
+    press(&mut app, KeyCode::Char(':'));
+    assert_eq!(app.mode(), Mode::FilterInput);
+    assert_eq!(app.handle_key(ctrl('c')), Action::Quit);
+    assert_eq!(app.handle_key(ctrl('z')), Action::Suspend);
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.handle_key(ctrl('c')), Action::Quit);
+}
+
+#[test]
+fn test_copy_menu() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.mode(), Mode::CopyMenu);
+    assert_snapshot("copy_menu", &screen(&mut app));
+
+    assert_eq!(
+        press(&mut app, KeyCode::Char('3')),
+        Action::Copy {
+            text: "http://localhost:3000\nhttp://localhost:3001".into(),
+            label: "URL".into()
+        }
+    );
+    assert_eq!(app.mode(), Mode::Normal);
+
+    // move to the summary and copy it with enter
+    press(&mut app, KeyCode::Enter);
+    for _ in 0..6 {
+        press(&mut app, KeyCode::Char('j'));
+    }
+    assert_eq!(
+        press(&mut app, KeyCode::Enter),
+        Action::Copy {
+            text: "node (pid 200) on 3000, 3001: /usr/bin/node --serve".into(),
+            label: "Summary".into()
+        }
+    );
+
+    // nothing to copy closes the menu with a message
+    press(&mut app, KeyCode::End);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(press(&mut app, KeyCode::Char('2')), Action::None);
+    assert_eq!(app.mode(), Mode::Normal);
+
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_copy_marked_processes() {
+    let mut app = app(mock_processes());
+    // marking moves down, so this marks postgres and redis-server
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    let text = screen(&mut app);
+    assert!(text.contains("| 2 marked"));
+    assert_eq!(text.matches('●').count(), 2);
+
+    // marks survive a filter that hides them
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "bash");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(
+        press(&mut app, KeyCode::Char('1')),
+        Action::Copy {
+            text: "100\n300".into(),
+            label: "PID of 2 processes".into()
+        }
+    );
+
+    // processes that are gone are unmarked
+    app.set_processes(vec![server(300, "redis-server").ports(&[6379]).build()]);
+    let pids: Vec<i32> = app.copy_targets().iter().map(|p| p.pid).collect();
+    assert_eq!(pids, [300]);
+}
+
+#[test]
+fn test_copy_text() {
+    let node = server(200, "node").ports(&[3000, 3001]).build();
+    let bash = server(400, "bash").cwd("").build();
+    let both = [&node, &bash];
+
+    assert_eq!(
+        copy_text(CopyTarget::Ports, &both).as_deref(),
+        Some("3000\n3001")
+    );
+    assert_eq!(copy_text(CopyTarget::Url, &[&bash]), None);
+    assert_eq!(copy_text(CopyTarget::Cwd, &both).as_deref(), Some("/srv"));
+    assert_eq!(
+        copy_text(CopyTarget::Exe, &both).as_deref(),
+        Some("/usr/bin/node\n/usr/bin/bash")
+    );
+    assert_eq!(
+        copy_text(CopyTarget::Summary, &[&bash]).as_deref(),
+        Some("bash (pid 400): /usr/bin/bash --serve")
+    );
+
+    let json = copy_text(CopyTarget::Json, &both).unwrap();
+    let lines: Vec<serde_json::Value> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["pid"], 200);
+    assert_eq!(lines[1]["name"], "bash");
+}
+
+#[test]
+fn test_layout_screens() {
+    let mut app = app(mock_processes());
+    sized_screen(&mut app, 120, 30);
+    press(&mut app, KeyCode::Down);
+
+    press(&mut app, KeyCode::Char('v'));
+    assert_snapshot("layout_right", &sized_screen(&mut app, 120, 30));
+    press(&mut app, KeyCode::Char('v'));
+    assert_snapshot("layout_bottom", &sized_screen(&mut app, 120, 30));
+    press(&mut app, KeyCode::Char('v'));
+    assert!(!sized_screen(&mut app, 120, 30).contains("Process Detail"));
+}
+
+#[test]
+fn test_layout_falls_back_on_small_terminals() {
+    let mut app = app(mock_processes());
+    sized_screen(&mut app, 80, 30);
+
+    // too narrow for the right pane, so it goes below the list
+    press(&mut app, KeyCode::Char('v'));
+    let text = sized_screen(&mut app, 80, 30);
+    assert!(text.contains("showing list + bottom pane"));
+    assert!(text.contains("Process Detail"));
+
+    // and at the default test size there is no room for either
+    assert!(!screen(&mut app).contains("Process Detail"));
+}
+
+fn click(app: &mut App, column: u16, row: u16) -> Action {
+    app.handle_mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn test_mouse_sorting_and_selection() {
+    let mut app = app(mock_processes());
+    screen(&mut app);
+
+    // the column titles are on the fifth line; PID starts at the left border
+    click(&mut app, 1, 4);
+    click(&mut app, 1, 4);
+    let pids: Vec<i32> = app.shown().iter().map(|p| p.pid).collect();
+    assert_eq!(pids, vec![400, 300, 200, 100]);
+    assert!(screen(&mut app).contains("PID ▼"));
+
+    // a double click on a row opens its details
+    click(&mut app, 12, 6);
+    assert_eq!(app.selected().map(|p| p.pid), Some(300));
+    assert_eq!(click(&mut app, 12, 6), Action::LoadDetails(300));
+    assert_eq!(app.mode(), Mode::Detail);
+
+    // tab titles switch tabs, a click outside closes the view
+    let text = screen(&mut app);
+    let row = text
+        .lines()
+        .position(|l| l.contains(" Overview │"))
+        .unwrap();
+    let column = text.lines().nth(row).unwrap().find("Limits").unwrap();
+    let column = text.lines().nth(row).unwrap()[..column].chars().count();
+    click(&mut app, column as u16, row as u16);
+    assert_eq!(app.detail_lines(), vec!["Loading...".to_string()]);
+    click(&mut app, 0, 0);
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+/// the column and row where `needle` first shows on `text`
+fn position_of(text: &str, needle: &str) -> (u16, u16) {
+    let (row, line) = text
+        .lines()
+        .enumerate()
+        .find(|(_, l)| l.contains(needle))
+        .unwrap_or_else(|| panic!("`{needle}` is not on the screen:\n{text}"));
+    let column = line[..line.find(needle).unwrap()].chars().count();
+    (column as u16, row as u16)
+}
+
+fn click_on(app: &mut App, needle: &str) -> Action {
+    let (column, row) = position_of(&screen(app), needle);
+    click(app, column, row)
+}
+
+#[test]
+fn test_mouse_table_hits() {
+    // an exposed process and a marked one add the marker and mark columns
+    // in front of the titles
+    let mut processes = mock_processes();
+    processes[0] = process(100, "postgres").listen("0.0.0.0", 5432).build();
+    let mut app = app(processes);
+    press(&mut app, KeyCode::Char(' '));
+    assert!(screen(&mut app).contains("exposed"));
+
+    click_on(&mut app, "Ports");
+    assert!(screen(&mut app).contains("Ports ▲"));
+    click_on(&mut app, "Name");
+    assert!(screen(&mut app).contains("Name ▲"));
+    let names: Vec<&str> = app.shown().iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["bash", "node", "postgres", "redis-server"]);
+
+    // the blank titles of the front columns do not sort
+    let (_, row) = position_of(&screen(&mut app), "Name ▲");
+    click(&mut app, 1, row);
+    assert!(screen(&mut app).contains("Name ▲"));
+
+    click_on(&mut app, "redis-server");
+    assert_eq!(app.selected().map(|p| p.pid), Some(300));
+    // below the last row and outside the table nothing changes
+    click(&mut app, 5, HEIGHT - 3);
+    click(&mut app, WIDTH - 1, HEIGHT - 1);
+    assert_eq!(app.selected().map(|p| p.pid), Some(300));
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_mouse_kill_buttons() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(click_on(&mut app, "[ No ]"), Action::None);
+    assert_eq!(app.mode(), Mode::Normal);
+
+    // a click beside the buttons cancels too
+    press(&mut app, KeyCode::Char('x'));
+    let (column, row) = position_of(&screen(&mut app), "[ Yes ]");
+    assert_eq!(click(&mut app, column - 2, row), Action::None);
+    assert_eq!(app.mode(), Mode::Normal);
+
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(click_on(&mut app, "[ Yes ]"), Action::Kill(100));
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(click_on(&mut app, "Yes ]"), Action::Kill(100));
+}
+
+#[test]
+fn test_mouse_column_chooser() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Char('c'));
+    click_on(&mut app, "[ ] User");
+    click_on(&mut app, "[x] Command");
+    assert!(screen(&mut app).contains("[x] User"));
+
+    // the hint line and the border are not items
+    click_on(&mut app, "space: toggle");
+    let (column, row) = position_of(&screen(&mut app), "[x] PID");
+    click(&mut app, column - 1, row);
+    assert_eq!(app.mode(), Mode::Columns);
+
+    assert_eq!(
+        press(&mut app, KeyCode::Enter),
+        Action::SaveColumns(vec![Column::Pid, Column::Name, Column::Ports, Column::User])
+    );
+}
+
+#[test]
+fn test_mouse_copy_menu() {
+    let mut app = app(mock_processes());
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.mode(), Mode::CopyMenu);
+    assert_eq!(
+        click_on(&mut app, "Working directory"),
+        Action::Copy {
+            text: "/srv".into(),
+            label: "Working directory".into()
+        }
+    );
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(click(&mut app, 0, HEIGHT - 1), Action::None);
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_snapshot_cannot_be_killed() {
+    let options = TuiOptions {
+        columns: Column::DEFAULT.to_vec(),
+        refresh: Duration::ZERO,
+        snapshot: Some("before.json".into()),
+        ..TuiOptions::default()
+    };
+    let mut app = App::new(mock_processes(), options, FilterHistory::new(vec![]));
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(app.mode(), Mode::Normal);
+    assert_eq!(press(&mut app, KeyCode::Char('r')), Action::None);
+}
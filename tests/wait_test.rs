@@ -0,0 +1,52 @@
+use portsage::wait::{wait_for_port, WaitFor};
+use std::time::Duration;
+
+#[test]
+fn test_wait_until_listening() {
+    let mut polls = 0;
+    let owner = wait_for_port(8080, WaitFor::Listening, None, Duration::ZERO, |_| {
+        polls += 1;
+        Ok((polls >= 3).then_some(4242))
+    })
+    .unwrap();
+
+    assert_eq!(owner, Some(4242));
+    assert_eq!(polls, 3);
+}
+
+#[test]
+fn test_wait_until_free() {
+    let mut polls = 0;
+    let owner = wait_for_port(5432, WaitFor::Free, None, Duration::ZERO, |_| {
+        polls += 1;
+        Ok((polls < 2).then_some(987))
+    })
+    .unwrap();
+
+    assert_eq!(owner, None);
+}
+
+#[test]
+fn test_wait_times_out() {
+    let result = wait_for_port(
+        8080,
+        WaitFor::Listening,
+        Some(Duration::from_millis(30)),
+        Duration::from_millis(10),
+        |_| Ok(None),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_wait_stops_on_lookup_errors() {
+    let mut polls = 0;
+    let result = wait_for_port(8080, WaitFor::Listening, None, Duration::ZERO, |_| {
+        polls += 1;
+        Err(anyhow::anyhow!("failed to run lsof"))
+    });
+
+    assert_eq!(result.unwrap_err().to_string(), "failed to run lsof");
+    assert_eq!(polls, 1);
+}
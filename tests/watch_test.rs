@@ -0,0 +1,76 @@
+mod common;
+
+use common::process;
+use portsage::watch::{diff_listeners, exited_processes, listeners, shorten_home, ChangeKind};
+
+#[test]
+fn test_diff_listeners() {
+    let before = listeners(&[
+        process(987, "postgres")
+            .cwd("/var/lib/pg")
+            .ports(&[5432])
+            .build(),
+        process(1234, "node").cwd("/srv/web").ports(&[3000]).build(),
+    ]);
+    let after = listeners(&[
+        process(1234, "node")
+            .cwd("/srv/web")
+            .ports(&[3000, 8080])
+            .build(),
+        process(2000, "redis").cwd("/").ports(&[6379]).build(),
+    ]);
+
+    let events = diff_listeners(&before, &after, 42);
+    let lines: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+
+    assert_eq!(
+        lines,
+        [
+            "- 5432 postgres[987]",
+            "+ 6379 redis[2000] /",
+            "+ 8080 node[1234] /srv/web"
+        ]
+    );
+    assert_eq!(events[0].event, ChangeKind::Close);
+    assert_eq!(
+        serde_json::to_string(&events[2]).unwrap(),
+        r#"{"event":"open","port":8080,"pid":1234,"name":"node","cwd":"/srv/web","timestamp":42}"#
+    );
+}
+
+#[test]
+fn test_no_events_without_changes() {
+    let current = listeners(&[process(1234, "node").cwd("/srv/web").ports(&[3000]).build()]);
+    assert!(diff_listeners(&current, &current, 0).is_empty());
+}
+
+#[test]
+fn test_shorten_home() {
+    let home = std::env::var("HOME").unwrap_or_default();
+    if home.is_empty() || home == "/" {
+        return;
+    }
+    assert_eq!(shorten_home(&format!("{home}/web")), "~/web");
+    assert_eq!(shorten_home(&home), "~");
+    assert_eq!(
+        shorten_home(&format!("{home}x/web")),
+        format!("{home}x/web")
+    );
+}
+
+#[test]
+fn test_exited_processes() {
+    let before = vec![
+        process(10, "ssh").cwd("/").build(),
+        process(20, "node").cwd("/").ports(&[3000]).build(),
+    ];
+    let mut reused = process(20, "node").cwd("/").ports(&[3000]).build();
+    reused.start_time = 99;
+    let after = vec![reused];
+
+    let exited: Vec<i32> = exited_processes(&before, &after)
+        .iter()
+        .map(|p| p.pid)
+        .collect();
+    assert_eq!(exited, [10, 20]);
+}